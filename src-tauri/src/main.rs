@@ -1,35 +1,85 @@
-#![cfg_attr(
-    all(not(debug_assertions), target_os = "windows"),
-    windows_subsystem = "windows"
-)]
-
-mod services;
-
-fn main() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_clipboard_manager::init())
-        .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![
-            // Mod commands
-            services::mod_service::get_folder_mods,
-            services::mod_service::set_mod_thumbnail,
-            services::mod_service::read_image_file,
-            services::mod_service::set_mod_info,
-            services::mod_service::download_mod,
-            services::mod_service::delete_mod,
-            services::mod_service::add_mod,
-            // Character commands
-            services::character::scrape_characters,
-            // Game commands
-            services::game::send_f10,
-            // Preset commands
-            services::preset::save_preset,
-            services::preset::get_presets,
-            services::preset::delete_preset,
-            services::preset::apply_preset,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
+#![cfg_attr(
+    all(not(debug_assertions), target_os = "windows"),
+    windows_subsystem = "windows"
+)]
+
+mod services;
+
+fn main() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_dialog::init())
+        .manage(services::watcher::LibraryWatcher::default())
+        .manage(services::image_protocol::ModImageServer::default())
+        .manage(services::index::OpenLibrary::default())
+        .register_asynchronous_uri_scheme_protocol(
+            services::image_protocol::SCHEME,
+            |ctx, request, responder| {
+                // Decoding and resizing must not block the webview thread
+                let app = ctx.app_handle().clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    responder.respond(services::image_protocol::handle_request(&app, &request));
+                });
+            },
+        )
+        .invoke_handler(tauri::generate_handler![
+            // Mod commands
+            services::mod_service::get_folder_mods,
+            services::mod_service::set_mod_thumbnail,
+            services::mod_service::set_mod_thumbnail_from_clipboard,
+            services::mod_service::read_image_file,
+            services::texture::read_texture_preview,
+            services::mod_service::set_mod_info,
+            services::mod_service::download_mod,
+            services::mod_service::delete_mod,
+            services::mod_service::add_mod,
+            services::bulk::bulk_update_mods,
+            services::index::rebuild_library_index,
+            services::query::query_mods,
+            services::search::search_mods,
+            services::ini::set_ini_enabled,
+            services::enable_state::normalize_disabled_prefixes,
+            // Journal commands
+            services::journal::get_journal_history,
+            services::journal::undo_last,
+            services::journal::redo,
+            // Trash commands
+            services::trash::list_trash,
+            services::trash::restore_from_trash,
+            services::trash::purge_trash,
+            // Settings commands
+            services::settings::get_library_settings,
+            services::settings::set_library_settings,
+            // Library watcher commands
+            services::watcher::watch_mods_folder,
+            services::watcher::unwatch_mods_folder,
+            // Conflict commands
+            services::conflict::find_conflicts,
+            services::keybind::find_keybind_conflicts,
+            services::keybind::rebind_key,
+            // Validation commands
+            services::validation::validate_mod,
+            services::validation::validate_library,
+            // Hash remap commands
+            services::hash_remap::remap_hashes,
+            services::hash_remap::list_hash_remap_backups,
+            services::hash_remap::undo_hash_remap,
+            // d3dx_user.ini commands
+            services::user_vars::get_mod_user_vars,
+            services::user_vars::set_mod_user_var,
+            services::user_vars::reset_mod_user_vars,
+            // Character commands
+            services::character::scrape_characters,
+            // Game commands
+            services::game::send_f10,
+            // Preset commands
+            services::preset::save_preset,
+            services::preset::get_presets,
+            services::preset::delete_preset,
+            services::preset::apply_preset,
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
@@ -0,0 +1,396 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::conflict::{conflicts_involving, HashConflict};
+use crate::services::discovery::ensure_group_enabled;
+use crate::services::enable_state::{name_for_state, parse_name, DisabledPrefix};
+use crate::services::error::AppError;
+use crate::services::index::load_library;
+use crate::services::journal::{read_text, record, FsChange};
+use crate::services::mod_json::{
+    normalize_tags, now_millis, read_mod_json, save_mod_json, ModJson, MOD_JSON_FILE_NAME,
+};
+use crate::services::settings::load_settings;
+use crate::services::trash::trash_mod;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BulkAction {
+    Enable,
+    Disable,
+    SetCategory { category: String },
+    AddTag { tag: String },
+    RemoveTag { tag: String },
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkOperation {
+    pub mod_id: String,
+    pub action: BulkAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkStatus {
+    Applied,
+    // Nothing to do, e.g. enabling a mod that already is
+    Unchanged,
+    Failed,
+    RolledBack,
+    // Not attempted because an earlier step failed and the batch was rolled back
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkResult {
+    pub mod_id: String,
+    pub mod_name: String,
+    pub action: BulkAction,
+    pub status: BulkStatus,
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkReport {
+    // One entry per operation, in the order they were given
+    pub results: Vec<BulkResult>,
+    pub rolled_back: bool,
+    // Hash conflicts caused by the mods this batch enabled
+    pub conflicts: Vec<HashConflict>,
+}
+
+/// Runs many enable/disable/category/tag/delete operations in one pass. With
+/// `rollback_on_error`, the first failure undoes every step already applied and skips the
+/// rest; otherwise the remaining operations still run and failures are only reported.
+/// Whatever is kept becomes a single journal entry, so the whole batch undoes in one go.
+#[tauri::command]
+pub async fn bulk_update_mods(
+    path: String,
+    operations: Vec<BulkOperation>,
+    rollback_on_error: bool,
+) -> Result<BulkReport, AppError> {
+    let root = Path::new(&path);
+
+    if !root.exists() || !root.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let mods = load_library(root).await?;
+    let names = mods
+        .iter()
+        .map(|mod_data| (mod_data.id.clone(), mod_data.name.clone()))
+        .collect::<HashMap<_, _>>();
+    let mod_dirs = mods
+        .into_iter()
+        .map(|mod_data| (mod_data.id, PathBuf::from(mod_data.path)))
+        .collect::<HashMap<_, _>>();
+
+    let (results, rolled_back, newly_enabled) =
+        run_operations(root, mod_dirs, &names, operations, rollback_on_error);
+    let conflicts = conflicts_involving(root, &newly_enabled).await;
+
+    Ok(BulkReport {
+        results,
+        rolled_back,
+        conflicts,
+    })
+}
+
+// Returns each operation's result, whether the batch was rolled back and the mods it enabled
+fn run_operations(
+    root: &Path,
+    // Current folder of each mod, updated as operations rename them
+    mut mod_dirs: HashMap<String, PathBuf>,
+    names: &HashMap<String, String>,
+    operations: Vec<BulkOperation>,
+    rollback_on_error: bool,
+) -> (Vec<BulkResult>, bool, Vec<String>) {
+    let prefix = load_settings(root).disabled_prefix;
+
+    let mut results = Vec::with_capacity(operations.len());
+    // Applied changes with the position of the operation that made them
+    let mut changes: Vec<(usize, FsChange)> = Vec::new();
+    let mut newly_enabled = Vec::new();
+    let mut failed = false;
+
+    for operation in operations {
+        let mut result = BulkResult {
+            mod_name: names.get(&operation.mod_id).cloned().unwrap_or_default(),
+            mod_id: operation.mod_id.clone(),
+            action: operation.action.clone(),
+            status: BulkStatus::Skipped,
+            error: None,
+        };
+
+        if failed && rollback_on_error {
+            results.push(result);
+            continue;
+        }
+
+        let applied = match mod_dirs.get(&operation.mod_id) {
+            Some(mod_dir) => apply(root, mod_dir, &operation.action, prefix),
+            None => Err(AppError::not_found(format!(
+                "Mod {} is not in the library",
+                operation.mod_id
+            ))
+            .with_mod_id(&operation.mod_id)),
+        };
+
+        match applied {
+            Ok(steps) if steps.is_empty() => result.status = BulkStatus::Unchanged,
+            Ok(steps) => {
+                for step in steps {
+                    match (&operation.action, &step) {
+                        (BulkAction::Delete, _) => {
+                            mod_dirs.remove(&operation.mod_id);
+                        }
+                        (_, FsChange::Move { to, .. }) => {
+                            mod_dirs.insert(operation.mod_id.clone(), to.clone());
+                        }
+                        _ => {}
+                    }
+                    changes.push((results.len(), step));
+                }
+                if operation.action == BulkAction::Enable {
+                    newly_enabled.push(operation.mod_id.clone());
+                }
+                result.status = BulkStatus::Applied;
+            }
+            Err(error) => {
+                eprintln!(
+                    "[bulk] {:?} on {}: {}",
+                    operation.action, operation.mod_id, error
+                );
+                result.status = BulkStatus::Failed;
+                result.error = Some(error);
+                failed = true;
+            }
+        }
+
+        results.push(result);
+    }
+
+    let rolled_back = failed && rollback_on_error;
+    let mut kept = Vec::new();
+    if rolled_back {
+        println!("[bulk] Rolling back {} applied steps", changes.len());
+        for (position, change) in changes.into_iter().rev() {
+            match change.revert() {
+                Ok(()) if results[position].error.is_none() => {
+                    results[position].status = BulkStatus::RolledBack
+                }
+                Ok(()) => {}
+                Err(error) => {
+                    eprintln!("[bulk] Rollback failed: {}", error);
+                    results[position].status = BulkStatus::Applied;
+                    results[position].error = Some(AppError {
+                        message: format!("Rollback failed: {}", error.message),
+                        ..error
+                    });
+                    kept.insert(0, change);
+                }
+            }
+        }
+        newly_enabled.clear();
+    } else {
+        kept = changes.into_iter().map(|(_, change)| change).collect();
+    }
+
+    let mod_count = results
+        .iter()
+        .filter(|result| result.status == BulkStatus::Applied)
+        .count();
+    record(root, &format!("Bulk update of {} mods", mod_count), kept);
+
+    (results, rolled_back, newly_enabled)
+}
+
+// Applies one action to the mod in `mod_dir`, returning the changes made in the order they
+// were made. Empty when the mod was already in that state.
+fn apply(
+    root: &Path,
+    mod_dir: &Path,
+    action: &BulkAction,
+    prefix: DisabledPrefix,
+) -> Result<Vec<FsChange>, AppError> {
+    if !mod_dir.is_dir() {
+        return Err(AppError::not_found("Mod directory does not exist"));
+    }
+
+    match action {
+        BulkAction::Enable | BulkAction::Disable => {
+            let enabled = *action == BulkAction::Enable;
+            let current_name = mod_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| {
+                    AppError::invalid_input("Invalid mod directory name").with_path(mod_dir)
+                })?;
+
+            if enabled {
+                ensure_group_enabled(root, mod_dir)?;
+            }
+            if parse_name(current_name).0 == enabled {
+                return Ok(Vec::new());
+            }
+
+            let target = mod_dir.with_file_name(name_for_state(current_name, enabled, prefix));
+            if target.exists() {
+                return Err(AppError::already_exists(format!(
+                    "{} already exists",
+                    target.display()
+                ))
+                .with_path(&target));
+            }
+
+            fs::rename(mod_dir, &target).map_err(|e| AppError::io("Failed to rename mod", e))?;
+            let mut changes = vec![FsChange::Move {
+                from: mod_dir.to_path_buf(),
+                to: target.clone(),
+            }];
+
+            if enabled {
+                match update_mod_json(&target, |mod_json| {
+                    mod_json.last_enabled_at = Some(now_millis())
+                }) {
+                    Ok(step) => changes.push(step),
+                    // The mod is enabled either way, only the timestamp is missing
+                    Err(error) => eprintln!("[bulk] Failed to record enable time: {}", error),
+                }
+            }
+
+            Ok(changes)
+        }
+        BulkAction::SetCategory { category } => {
+            update_mod_json(mod_dir, |mod_json| mod_json.category = category.clone())
+                .map(|step| vec![step])
+        }
+        BulkAction::AddTag { tag } => update_mod_json(mod_dir, |mod_json| {
+            mod_json.tags.push(tag.clone());
+            mod_json.tags = normalize_tags(&mod_json.tags);
+        })
+        .map(|step| vec![step]),
+        BulkAction::RemoveTag { tag } => update_mod_json(mod_dir, |mod_json| {
+            mod_json
+                .tags
+                .retain(|existing| !existing.trim().eq_ignore_ascii_case(tag.trim()))
+        })
+        .map(|step| vec![step]),
+        BulkAction::Delete => trash_mod(root, mod_dir).map(|step| vec![step]),
+    }
+}
+
+fn update_mod_json(
+    mod_dir: &Path,
+    update: impl FnOnce(&mut ModJson),
+) -> Result<FsChange, AppError> {
+    let path = mod_dir.join(MOD_JSON_FILE_NAME);
+    let before = read_text(&path);
+
+    let mut mod_json = read_mod_json(mod_dir)?;
+    update(&mut mod_json);
+    save_mod_json(mod_dir, &mod_json)?;
+
+    Ok(FsChange::write(&path, before))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_back_applied_steps_when_one_fails() {
+        let root = std::env::temp_dir().join(format!("wuwa-bulk-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("disabled Alpha")).unwrap();
+        fs::create_dir_all(root.join("Beta")).unwrap();
+        fs::write(
+            root.join("Beta").join(MOD_JSON_FILE_NAME),
+            "{\"category\":\"Jinhsi\"}",
+        )
+        .unwrap();
+
+        let mod_dirs = HashMap::from([
+            ("alpha".to_string(), root.join("disabled Alpha")),
+            ("beta".to_string(), root.join("Beta")),
+        ]);
+        let operation = |mod_id: &str, action| BulkOperation {
+            mod_id: mod_id.to_string(),
+            action,
+        };
+        let operations = vec![
+            operation("alpha", BulkAction::Enable),
+            operation("beta", BulkAction::Delete),
+            operation(
+                "beta",
+                BulkAction::AddTag {
+                    tag: "summer".to_string(),
+                },
+            ),
+            operation("gamma", BulkAction::Disable),
+            operation("alpha", BulkAction::Disable),
+        ];
+
+        let (results, rolled_back, enabled) =
+            run_operations(&root, mod_dirs, &HashMap::new(), operations, true);
+        let statuses = results
+            .iter()
+            .map(|result| result.status)
+            .collect::<Vec<_>>();
+        let alpha_restored = root.join("disabled Alpha").is_dir();
+        let beta_json = fs::read_to_string(root.join("Beta").join(MOD_JSON_FILE_NAME)).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(rolled_back);
+        assert!(enabled.is_empty());
+        assert_eq!(
+            statuses,
+            vec![
+                BulkStatus::RolledBack,
+                BulkStatus::RolledBack,
+                BulkStatus::Failed,
+                BulkStatus::Skipped,
+                BulkStatus::Skipped,
+            ]
+        );
+        assert!(alpha_restored);
+        assert_eq!(beta_json, "{\"category\":\"Jinhsi\"}");
+    }
+
+    #[test]
+    fn refuses_to_enable_a_mod_below_a_disabled_group() {
+        let root = std::env::temp_dir().join(format!("wuwa-bulk-{}", uuid::Uuid::new_v4()));
+        let mod_dir = root.join("DISABLED_Characters").join("Jinhsi");
+        fs::create_dir_all(&mod_dir).unwrap();
+
+        let operations = vec![BulkOperation {
+            mod_id: "jinhsi".to_string(),
+            action: BulkAction::Enable,
+        }];
+        let (results, _, enabled) = run_operations(
+            &root,
+            HashMap::from([("jinhsi".to_string(), mod_dir.clone())]),
+            &HashMap::new(),
+            operations,
+            false,
+        );
+        let untouched = mod_dir.is_dir();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(results[0].status, BulkStatus::Failed);
+        assert_eq!(
+            results[0]
+                .error
+                .as_ref()
+                .map(|error| error.message.as_str()),
+            Some("Parent group DISABLED_Characters is disabled")
+        );
+        assert!(enabled.is_empty());
+        assert!(untouched);
+    }
+}
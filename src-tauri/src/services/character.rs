@@ -2,10 +2,21 @@ use reqwest::{Client, Url};
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use crate::services::error::AppError;
 
 const CHARACTERS_URL: &str = "https://wuwa.akademiya.app/en/characters";
 const BASE_URL: &str = "https://wuwa.akademiya.app";
 
+static CARD_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(r#"main a[href^="/en/characters/"], main div.group, div.group"#).unwrap()
+});
+static NAME_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("div.neutral-text").unwrap());
+static IMAGE_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("div.overflow-hidden img, img").unwrap());
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CharacterScrape {
@@ -14,27 +25,27 @@ pub struct CharacterScrape {
 }
 
 #[tauri::command]
-pub async fn scrape_characters() -> Result<Vec<CharacterScrape>, String> {
+pub async fn scrape_characters() -> Result<Vec<CharacterScrape>, AppError> {
     println!("[character-scraper] Fetching characters from {CHARACTERS_URL}");
 
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .user_agent("wuwa-mod-manager/0.1.0")
         .build()
-        .map_err(|e| format!("HTTP client error: {e}"))?;
+        .map_err(|e| AppError::network("HTTP client error", e))?;
 
     let response = client
         .get(CHARACTERS_URL)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {e}"))?
+        .map_err(|e| AppError::network("Request failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Request failed: {e}"))?;
+        .map_err(|e| AppError::network("Request failed", e))?;
 
     let html = response
         .text()
         .await
-        .map_err(|e| format!("Failed to read response: {e}"))?;
+        .map_err(|e| AppError::network("Failed to read response", e))?;
 
     println!("[character-scraper] HTML downloaded, starting parse");
 
@@ -42,7 +53,9 @@ pub async fn scrape_characters() -> Result<Vec<CharacterScrape>, String> {
 
     if characters.is_empty() {
         eprintln!("[character-scraper] Parse finished but no characters were found");
-        return Err("No characters found in scraped HTML".to_string());
+        return Err(AppError::invalid_data(
+            "No characters found in scraped HTML",
+        ));
     }
 
     println!(
@@ -53,29 +66,20 @@ pub async fn scrape_characters() -> Result<Vec<CharacterScrape>, String> {
     Ok(characters)
 }
 
-fn parse_characters(html: &str) -> Result<Vec<CharacterScrape>, String> {
+fn parse_characters(html: &str) -> Result<Vec<CharacterScrape>, AppError> {
     let document = Html::parse_document(html);
 
-    let card_selector = Selector::parse(r#"main a[href^="/en/characters/"], main div.group, div.group"#)
-        .map_err(|e| format!("Selector error: {e}"))?;
-
-    let name_selector = Selector::parse("div.neutral-text")
-        .map_err(|e| format!("Selector error: {e}"))?;
-
-    let image_selector = Selector::parse("div.overflow-hidden img, img")
-        .map_err(|e| format!("Selector error: {e}"))?;
-
     let mut characters = Vec::new();
     let mut seen = HashSet::new();
 
-    for card in document.select(&card_selector) {
+    for card in document.select(&CARD_SELECTOR) {
         let name = card
-            .select(&name_selector)
+            .select(&NAME_SELECTOR)
             .next()
             .map(|element| element.text().collect::<String>().trim().to_string())
             .filter(|name| !name.is_empty());
 
-        let thumbnail = extract_thumbnail_from_card(&card, &image_selector);
+        let thumbnail = extract_thumbnail_from_card(&card, &IMAGE_SELECTOR);
 
         if let (Some(name), Some(thumbnail)) = (name, thumbnail) {
             let dedupe_key = format!("{}|{}", name.to_lowercase(), thumbnail);
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::services::error::AppError;
+use crate::services::index::load_library;
+use crate::services::mod_service::Mod;
+
+const TEXTURE_OVERRIDE_PREFIX: &str = "TextureOverride";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictingMod {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub category: String,
+    pub section: String,
+    pub file: String,
+}
+
+/// Enabled mods that override the same hash (and draw range) and will fight in game.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HashConflict {
+    pub hash: String,
+    pub match_first_index: Option<String>,
+    // Categories of the involved mods, usually a single character
+    pub characters: Vec<String>,
+    pub mods: Vec<ConflictingMod>,
+}
+
+#[tauri::command]
+pub async fn find_conflicts(path: String) -> Result<Vec<HashConflict>, AppError> {
+    let dir = Path::new(&path);
+
+    if !dir.exists() || !dir.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let mods = load_library(dir).await?;
+    Ok(detect_conflicts(&mods))
+}
+
+pub fn detect_conflicts(mods: &[Mod]) -> Vec<HashConflict> {
+    // Overrides of one hash with different `match_first_index` target different parts of
+    // the same mesh, so both are part of the key.
+    let mut by_hash: BTreeMap<(String, Option<String>), Vec<ConflictingMod>> = BTreeMap::new();
+
+    for mod_data in mods.iter().filter(|mod_data| mod_data.enabled) {
+        let mut seen = BTreeSet::new();
+
+        // Only texture overrides replace what is drawn, any number of mods can hook a shader
+        for hash in mod_data.ini.hashes.iter().filter(|hash| {
+            hash.section
+                .get(..TEXTURE_OVERRIDE_PREFIX.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(TEXTURE_OVERRIDE_PREFIX))
+        }) {
+            let key = (hash.hash.clone(), hash.match_first_index.clone());
+
+            // A mod overriding its own hash in several sections is not a conflict
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+
+            by_hash.entry(key).or_default().push(ConflictingMod {
+                id: mod_data.id.clone(),
+                name: mod_data.name.clone(),
+                path: mod_data.path.clone(),
+                category: mod_data.category.clone(),
+                section: hash.section.clone(),
+                file: hash.file.clone(),
+            });
+        }
+    }
+
+    let mut conflicts = by_hash
+        .into_iter()
+        .filter(|(_, mods)| mods.len() > 1)
+        .map(|((hash, match_first_index), mods)| HashConflict {
+            hash,
+            match_first_index,
+            characters: mods
+                .iter()
+                .map(|mod_data| mod_data.category.clone())
+                .filter(|category| !category.is_empty())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            mods,
+        })
+        .collect::<Vec<_>>();
+
+    conflicts.sort_by(|a, b| a.characters.cmp(&b.characters).then(a.hash.cmp(&b.hash)));
+    conflicts
+}
+
+/// Conflicts in the library that involve at least one of `mod_ids`.
+pub async fn conflicts_involving(root: &Path, mod_ids: &[String]) -> Vec<HashConflict> {
+    if mod_ids.is_empty() {
+        return Vec::new();
+    }
+
+    match load_library(root).await {
+        Ok(mods) => detect_conflicts(&mods)
+            .into_iter()
+            .filter(|conflict| {
+                conflict
+                    .mods
+                    .iter()
+                    .any(|mod_data| mod_ids.contains(&mod_data.id))
+            })
+            .collect(),
+        Err(error) => {
+            eprintln!(
+                "[conflicts] Failed to load library for conflict check: {}",
+                error
+            );
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ini::OverrideHash;
+    use serde_json::json;
+
+    fn mod_overriding(name: &str, sections: &[&str]) -> Mod {
+        let mut mod_data: Mod = serde_json::from_value(json!({
+            "id": name,
+            "author": "",
+            "description": "",
+            "version": "",
+            "category": "Jinhsi",
+            "url": "",
+            "name": name,
+            "path": format!("/mods/{}", name),
+            "thumbnail": "",
+            "enabled": true,
+        }))
+        .unwrap();
+        mod_data.ini.hashes = sections
+            .iter()
+            .map(|section| OverrideHash {
+                hash: "1a2b3c4d".to_string(),
+                section: section.to_string(),
+                file: "mod.ini".to_string(),
+                match_first_index: None,
+            })
+            .collect();
+        mod_data
+    }
+
+    #[test]
+    fn reports_texture_overrides_but_not_shared_shader_hooks() {
+        let shaders = detect_conflicts(&[
+            mod_overriding("Outline", &["ShaderOverrideOutline"]),
+            mod_overriding("Glow", &["ShaderRegexGlow"]),
+        ]);
+        let textures = detect_conflicts(&[
+            mod_overriding("Summer", &["TextureOverrideBody"]),
+            mod_overriding("Winter", &["textureoverridebody", "ShaderOverrideWinter"]),
+        ]);
+
+        assert!(shaders.is_empty());
+        assert_eq!(textures.len(), 1);
+        assert_eq!(textures[0].mods.len(), 2);
+    }
+}
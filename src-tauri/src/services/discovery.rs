@@ -0,0 +1,210 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::enable_state::is_disabled_name;
+use crate::services::error::AppError;
+
+// Grouped libraries rarely go deeper than Mods/<Group>/<Character>/<Skin>, this only guards
+// against symlink loops and runaway archive layouts.
+const MAX_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModLocation {
+    pub path: PathBuf,
+    // Parent folders between the library root and the mod, joined with '/'
+    pub group: String,
+}
+
+/// Walks the library and returns every folder that should be shown as its own mod.
+///
+/// A folder is a mod root when it directly contains a 3DMigoto `.ini` or a `mod.json`.
+/// Mod roots are not descended into, so `Mods/Characters/Jinhsi/SkinA` is found as one mod
+/// grouped under `Characters/Jinhsi`. Top-level folders without any mod roots are still
+/// listed so empty or half-installed mods stay visible.
+pub fn discover_mods(root: &Path) -> Result<Vec<ModLocation>, AppError> {
+    let mut locations = Vec::new();
+    walk(root, root, 0, &mut locations)?;
+    Ok(locations)
+}
+
+pub fn is_mod_root(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        path.is_file() && (is_ini_file(&path) || entry.file_name() == "mod.json")
+    })
+}
+
+pub fn is_ini_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("ini"))
+        .unwrap_or(false)
+}
+
+/// Relative parent path of `path` inside `root`, or an empty string for top-level mods.
+pub fn group_of(root: &Path, path: &Path) -> String {
+    path.parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|relative| {
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_default()
+}
+
+/// Whether a folder between the library root and the mod is disabled. 3DMigoto skips
+/// everything below such a folder, whatever the mod's own folder is called.
+pub fn in_disabled_group(group: &str) -> bool {
+    group.split('/').any(is_disabled_name)
+}
+
+/// Fails when a folder between the library root and `mod_dir` is disabled, since enabling
+/// the mod alone changes nothing in game. The group is left to the user, it may hold other
+/// mods.
+pub fn ensure_group_enabled(root: &Path, mod_dir: &Path) -> Result<(), AppError> {
+    match group_of(root, mod_dir)
+        .split('/')
+        .find(|folder| is_disabled_name(folder))
+    {
+        Some(folder) => Err(AppError::invalid_input(format!(
+            "Parent group {} is disabled",
+            folder
+        ))
+        .with_path(mod_dir)),
+        None => Ok(()),
+    }
+}
+
+/// Library root a mod was discovered from, given its folder and group.
+pub fn library_root(mod_path: &Path, group: &str) -> PathBuf {
+    let mut root = mod_path.parent().unwrap_or(mod_path).to_path_buf();
+    for _ in group.split('/').filter(|part| !part.is_empty()) {
+        root.pop();
+    }
+    root
+}
+
+fn walk(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    locations: &mut Vec<ModLocation>,
+) -> Result<(), AppError> {
+    if depth >= MAX_DEPTH {
+        return Ok(());
+    }
+
+    let mut children = fs::read_dir(dir)
+        .map_err(|e| AppError::io(format!("Failed to read {}", dir.display()), e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !is_hidden(path))
+        .collect::<Vec<_>>();
+    children.sort();
+
+    for child in children {
+        if is_mod_root(&child) {
+            locations.push(ModLocation {
+                group: group_of(root, &child),
+                path: child,
+            });
+            continue;
+        }
+
+        let found_before = locations.len();
+        if let Err(error) = walk(root, &child, depth + 1, locations) {
+            eprintln!("[mods] Skipping unreadable folder: {}", error);
+        }
+
+        if depth == 0 && locations.len() == found_before {
+            locations.push(ModLocation {
+                group: String::new(),
+                path: child,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with('.'))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_library(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("wuwa-discovery-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn finds_nested_mod_roots_with_their_group() {
+        let root = temp_library("nested");
+        let skin = root.join("Characters").join("Jinhsi").join("SkinA");
+        fs::create_dir_all(skin.join("Textures")).unwrap();
+        fs::write(
+            skin.join("SkinA.ini"),
+            "[TextureOverrideBody]\nhash = 1234abcd\n",
+        )
+        .unwrap();
+        fs::write(skin.join("Textures").join("extra.ini"), "").unwrap();
+
+        let flat = root.join("Changli");
+        fs::create_dir_all(&flat).unwrap();
+        fs::write(flat.join("mod.json"), "{}").unwrap();
+
+        let mods = discover_mods(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            mods,
+            vec![
+                ModLocation {
+                    path: root.join("Changli"),
+                    group: String::new(),
+                },
+                ModLocation {
+                    path: skin,
+                    group: "Characters/Jinhsi".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn disables_mods_below_a_disabled_group() {
+        assert!(in_disabled_group("DISABLED_Characters/Jinhsi"));
+        assert!(in_disabled_group("Characters/disabled Jinhsi"));
+        assert!(!in_disabled_group("Characters/Jinhsi"));
+        assert!(!in_disabled_group(""));
+    }
+
+    #[test]
+    fn keeps_empty_top_level_folders_and_skips_hidden_ones() {
+        let root = temp_library("empty");
+        fs::create_dir_all(root.join("Broken")).unwrap();
+        fs::create_dir_all(root.join(".cache").join("Inner")).unwrap();
+        fs::write(root.join(".cache").join("Inner").join("a.ini"), "").unwrap();
+
+        let mods = discover_mods(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(mods.len(), 1);
+        assert_eq!(mods[0].path, root.join("Broken"));
+    }
+}
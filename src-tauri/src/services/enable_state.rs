@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::services::discovery::discover_mods;
+use crate::services::error::AppError;
+use crate::services::journal::{read_text, record, FsChange};
+use crate::services::settings::{load_settings, save_settings, SETTINGS_FILE_NAME};
+use crate::services::storage::state_file;
+
+// 3DMigoto (via XXMI's `exclude_recursive = DISABLED*`) ignores any file or folder whose
+// name starts with "disabled" in any casing, so that is all detection needs to check.
+const DISABLED_MARKER: &str = "disabled";
+
+/// Prefix written in front of a disabled mod folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisabledPrefix {
+    /// `disabled Foo`, what this manager has always written
+    #[default]
+    Legacy,
+    /// `DISABLED_Foo`, the XXMI Launcher convention
+    Xxmi,
+    /// `DISABLED Foo`
+    XxmiSpace,
+}
+
+impl DisabledPrefix {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DisabledPrefix::Legacy => "disabled ",
+            DisabledPrefix::Xxmi => "DISABLED_",
+            DisabledPrefix::XxmiSpace => "DISABLED ",
+        }
+    }
+}
+
+/// Splits a folder or file name into its enabled state and the name without any
+/// disabled prefix: `DISABLED_Foo`, `DISABLED Foo`, `disabled_foo` and `disabledFoo`
+/// are all disabled.
+pub fn parse_name(name: &str) -> (bool, &str) {
+    match strip_disabled_prefix(name) {
+        Some(stripped) => (false, stripped),
+        None => (true, name),
+    }
+}
+
+pub fn is_disabled_name(name: &str) -> bool {
+    strip_disabled_prefix(name).is_some()
+}
+
+pub fn strip_disabled_prefix(name: &str) -> Option<&str> {
+    let marker = name.get(..DISABLED_MARKER.len())?;
+    if !marker.eq_ignore_ascii_case(DISABLED_MARKER) {
+        return None;
+    }
+
+    let rest = &name[DISABLED_MARKER.len()..];
+    Some(rest.strip_prefix(['_', ' ']).unwrap_or(rest))
+}
+
+/// Name to give a folder or file for the requested state, using `prefix` when disabled.
+pub fn name_for_state(name: &str, enabled: bool, prefix: DisabledPrefix) -> String {
+    let (_, bare) = parse_name(name);
+
+    if enabled {
+        bare.to_string()
+    } else {
+        format!("{}{}", prefix.as_str(), bare)
+    }
+}
+
+/// A disabled mod folder renamed to the library's prefix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefixRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Stores `prefix` as the library's convention and renames every disabled mod folder
+/// written with another variant to use it.
+#[tauri::command]
+pub fn normalize_disabled_prefixes(
+    path: String,
+    prefix: DisabledPrefix,
+) -> Result<Vec<PrefixRename>, AppError> {
+    let root = Path::new(&path);
+
+    if !root.exists() || !root.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let settings_path = state_file(root, SETTINGS_FILE_NAME)?;
+    let before = read_text(&settings_path);
+    let mut settings = load_settings(root);
+    settings.disabled_prefix = prefix;
+    save_settings(root, &settings)?;
+
+    let mut changes = vec![FsChange::write(&settings_path, before)];
+    let mut renamed = Vec::new();
+
+    for location in discover_mods(root)? {
+        let Some(name) = location.path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !is_disabled_name(name) {
+            continue;
+        }
+
+        let new_name = name_for_state(name, false, prefix);
+        if new_name == name {
+            continue;
+        }
+
+        let new_path = location.path.with_file_name(&new_name);
+        if new_path.exists() {
+            eprintln!(
+                "[enable-state] Skipping {}, {} already exists",
+                name, new_name
+            );
+            continue;
+        }
+
+        if let Err(e) = fs::rename(&location.path, &new_path) {
+            // Keep what was already renamed undoable
+            record(root, "Change disabled prefix", changes);
+            return Err(
+                AppError::io(format!("Failed to rename {}", name), e).with_path(&location.path)
+            );
+        }
+        println!("[enable-state] Renamed '{}' to '{}'", name, new_name);
+        changes.push(FsChange::Move {
+            from: location.path.clone(),
+            to: new_path.clone(),
+        });
+
+        renamed.push(PrefixRename {
+            from: location.path.to_string_lossy().to_string(),
+            to: new_path.to_string_lossy().to_string(),
+        });
+    }
+
+    record(root, "Change disabled prefix", changes);
+    Ok(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_every_common_prefix_variant() {
+        for name in [
+            "disabled Foo",
+            "DISABLED_Foo",
+            "DISABLED Foo",
+            "disabled_Foo",
+            "DisabledFoo",
+        ] {
+            assert_eq!(parse_name(name), (false, "Foo"), "{}", name);
+        }
+        assert_eq!(parse_name("Foo"), (true, "Foo"));
+    }
+
+    #[test]
+    fn renames_with_the_chosen_prefix() {
+        assert_eq!(
+            name_for_state("disabled Foo", false, DisabledPrefix::Xxmi),
+            "DISABLED_Foo"
+        );
+        assert_eq!(
+            name_for_state("DISABLED_Foo", true, DisabledPrefix::Legacy),
+            "Foo"
+        );
+        assert_eq!(
+            name_for_state("Foo", false, DisabledPrefix::Legacy),
+            "disabled Foo"
+        );
+    }
+}
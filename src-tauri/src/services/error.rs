@@ -0,0 +1,154 @@
+use serde::Serialize;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// What went wrong, for the UI to pick a recovery action from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    // A file that exists but can't be parsed, e.g. a broken presets.json
+    InvalidData,
+    // Bad arguments from the UI, e.g. an unknown search field
+    InvalidInput,
+    DiskFull,
+    // An external program such as 7z is not installed
+    ToolMissing,
+    Network,
+    Io,
+    Other,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorContext {
+    pub path: Option<String>,
+    pub mod_id: Option<String>,
+    // HTTP status of a failed download
+    pub status: Option<u16>,
+}
+
+/// Error returned by every command, serialized as `{ code, message, context }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub context: ErrorContext,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> AppError {
+        AppError {
+            code,
+            message: message.into(),
+            context: ErrorContext::default(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> AppError {
+        AppError::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn already_exists(message: impl Into<String>) -> AppError {
+        AppError::new(ErrorCode::AlreadyExists, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> AppError {
+        AppError::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn invalid_data(message: impl Into<String>) -> AppError {
+        AppError::new(ErrorCode::InvalidData, message)
+    }
+
+    /// An io error with what was being done, e.g. `AppError::io("Failed to rename mod", e)`.
+    /// The code follows the error kind so a locked or full drive can be told apart.
+    pub fn io(message: impl fmt::Display, error: io::Error) -> AppError {
+        AppError::new(io_code(&error), format!("{}: {}", message, error))
+    }
+
+    /// A failed request, keeping the HTTP status when the server answered.
+    pub fn network(message: impl fmt::Display, error: reqwest::Error) -> AppError {
+        let status = error.status();
+        let app_error = AppError::new(ErrorCode::Network, format!("{}: {}", message, error));
+        match status {
+            Some(status) => app_error.with_status(status.as_u16()),
+            None => app_error,
+        }
+    }
+
+    pub fn with_path(mut self, path: &Path) -> AppError {
+        self.context.path = Some(path.to_string_lossy().to_string());
+        self
+    }
+
+    pub fn with_mod_id(mut self, mod_id: &str) -> AppError {
+        self.context.mod_id = Some(mod_id.to_string());
+        self
+    }
+
+    pub fn with_status(mut self, status: u16) -> AppError {
+        self.context.status = Some(status);
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<io::Error> for AppError {
+    fn from(error: io::Error) -> AppError {
+        AppError::new(io_code(&error), error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> AppError {
+        AppError::invalid_data(error.to_string())
+    }
+}
+
+fn io_code(error: &io::Error) -> ErrorCode {
+    match error.kind() {
+        io::ErrorKind::NotFound => ErrorCode::NotFound,
+        io::ErrorKind::AlreadyExists => ErrorCode::AlreadyExists,
+        io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+        io::ErrorKind::InvalidData => ErrorCode::InvalidData,
+        io::ErrorKind::InvalidInput => ErrorCode::InvalidInput,
+        io::ErrorKind::StorageFull => ErrorCode::DiskFull,
+        _ => ErrorCode::Io,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_context() {
+        let error = AppError::io(
+            "Failed to rename mod",
+            io::Error::from(io::ErrorKind::PermissionDenied),
+        )
+        .with_path(Path::new("Mods/Jinhsi"))
+        .with_mod_id("abc");
+
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "permissionDenied",
+                "message": "Failed to rename mod: permission denied",
+                "context": { "path": "Mods/Jinhsi", "modId": "abc", "status": null },
+            })
+        );
+    }
+}
@@ -3,10 +3,22 @@ use enigo::{
     Enigo, Key, Keyboard, Settings,
 };
 
+use crate::services::error::{AppError, ErrorCode};
+
 #[tauri::command]
-pub fn send_f10() {
-    let mut enigo = Enigo::new(&Settings::default()).unwrap();
+pub fn send_f10() -> Result<(), AppError> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| {
+        AppError::new(
+            ErrorCode::Io,
+            format!("Failed to connect to the input system: {e}"),
+        )
+    })?;
 
-    enigo.key(Key::F10, Press).unwrap();
-    enigo.key(Key::F10, Release).unwrap();
+    enigo
+        .key(Key::F10, Press)
+        .map_err(|e| AppError::new(ErrorCode::Io, format!("Failed to press F10: {e}")))?;
+    enigo
+        .key(Key::F10, Release)
+        .map_err(|e| AppError::new(ErrorCode::Io, format!("Failed to release F10: {e}")))?;
+    Ok(())
 }
@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::services::error::AppError;
+use crate::services::index::load_library;
+use crate::services::ini::list_ini_files;
+use crate::services::journal::{read_text, record, FsChange};
+use crate::services::mod_json::now_millis;
+use crate::services::mod_service::Mod;
+use crate::services::storage::{replace_atomic, state_dir, state_file, write_atomic};
+
+// Remaps maintained with the app, extended by `.wuwamoma/hash_remap.json` in the library
+const BUNDLED_TABLE: &str = include_str!("../../../src/assets/hash_remap.json");
+const LOCAL_TABLE_NAME: &str = "hash_remap.json";
+const BACKUP_DIR_NAME: &str = "hash_remap_backups";
+const MANIFEST_NAME: &str = "manifest.json";
+const TABLE_VERSION: u32 = 1;
+
+/// Hash changes of one game patch, `old -> new`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemapPatch {
+    pub game_version: String,
+    pub hashes: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemapTable {
+    pub version: u32,
+    // Oldest patch first, so a hash changed twice is followed to its latest value
+    pub patches: Vec<RemapPatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashChange {
+    pub file: String,
+    pub line: usize,
+    pub section: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModRemap {
+    pub mod_id: String,
+    pub mod_name: String,
+    pub mod_path: String,
+    pub changes: Vec<HashChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemapReport {
+    pub dry_run: bool,
+    // Backup to pass to `undo_hash_remap`, none for dry runs or when nothing changed
+    pub backup_id: Option<String>,
+    pub mods: Vec<ModRemap>,
+    // Inis with outdated hashes that aren't UTF-8, left untouched
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackedUpMod {
+    // Mods are found by id on undo, so renames in between don't matter
+    pub mod_id: String,
+    pub mod_name: String,
+    // Ini paths relative to the mod folder, saved as `<mod id>-<n>.ini.bak` in the backup
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemapBackup {
+    pub id: String,
+    pub created_at: u64,
+    pub mods: Vec<BackedUpMod>,
+}
+
+/// Rewrites outdated `hash =` lines in one mod (`mod_path`) or the whole library. With
+/// `dry_run` nothing is written and the report lists what would change.
+#[tauri::command]
+pub async fn remap_hashes(
+    path: String,
+    mod_path: Option<String>,
+    dry_run: bool,
+) -> Result<RemapReport, AppError> {
+    let root = Path::new(&path);
+
+    if !root.exists() || !root.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let remaps = load_remaps(root)?;
+    let mods = load_library(root)
+        .await?
+        .into_iter()
+        .filter(|mod_data| mod_path.as_ref().is_none_or(|path| &mod_data.path == path))
+        .collect::<Vec<_>>();
+
+    if let (Some(mod_path), true) = (&mod_path, mods.is_empty()) {
+        return Err(AppError::not_found(format!(
+            "{} is not a mod of this library",
+            mod_path
+        )));
+    }
+
+    let mut report = RemapReport {
+        dry_run,
+        backup_id: None,
+        mods: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut backup = RemapBackup {
+        id: Uuid::new_v4().to_string(),
+        created_at: now_millis(),
+        mods: Vec::new(),
+    };
+    let backup_dir = state_dir(root).join(BACKUP_DIR_NAME).join(&backup.id);
+    let mut journal_changes = Vec::new();
+
+    for mod_data in &mods {
+        let mut changes = Vec::new();
+
+        for ini in list_ini_files(Path::new(&mod_data.path)) {
+            let ini_path = Path::new(&mod_data.path).join(&ini.file);
+            let bytes = fs::read(&ini_path).map_err(|e| AppError::io("Failed to read ini", e))?;
+            let (rewritten, file_changes) =
+                rewrite_hashes(&String::from_utf8_lossy(&bytes), &ini.file, &remaps);
+
+            if file_changes.is_empty() {
+                continue;
+            }
+            // Writing the lossy decode back would mangle every other non-ASCII byte
+            let Ok(before) = String::from_utf8(bytes) else {
+                eprintln!(
+                    "[hash-remap] Skipping {}, it is not UTF-8",
+                    ini_path.display()
+                );
+                report.skipped.push(ini_path.to_string_lossy().to_string());
+                continue;
+            };
+            changes.extend(file_changes);
+
+            if dry_run {
+                continue;
+            }
+
+            fs::create_dir_all(&backup_dir)
+                .map_err(|e| AppError::io("Failed to create hash remap backup", e))?;
+            if backup
+                .mods
+                .last()
+                .is_none_or(|backed_up| backed_up.mod_id != mod_data.id)
+            {
+                backup.mods.push(BackedUpMod {
+                    mod_id: mod_data.id.clone(),
+                    mod_name: mod_data.name.clone(),
+                    files: Vec::new(),
+                });
+            }
+            let backed_up = backup.mods.len() - 1;
+            let files = &mut backup.mods[backed_up].files;
+            fs::write(backup_file(&backup_dir, &mod_data.id, files.len()), &before)
+                .map_err(|e| AppError::io("Failed to back up ini", e))?;
+            files.push(ini.file.clone());
+
+            // The manifest is saved before each ini changes so a run that fails halfway can
+            // still be undone
+            write_manifest(&backup_dir, &backup)?;
+            report.backup_id = Some(backup.id.clone());
+            replace_atomic(&ini_path, &rewritten)?;
+            journal_changes.push(FsChange::Write {
+                path: ini_path,
+                before: Some(before),
+                after: Some(rewritten),
+            });
+        }
+
+        if changes.is_empty() {
+            continue;
+        }
+
+        println!(
+            "[hash-remap] {} {} hashes in {}",
+            if dry_run { "Would remap" } else { "Remapped" },
+            changes.len(),
+            mod_data.name
+        );
+
+        report.mods.push(ModRemap {
+            mod_id: mod_data.id.clone(),
+            mod_name: mod_data.name.clone(),
+            mod_path: mod_data.path.clone(),
+            changes,
+        });
+    }
+
+    let label = match report.mods.as_slice() {
+        [remapped] => format!("Remap hashes of {}", remapped.mod_name),
+        remapped => format!("Remap hashes of {} mods", remapped.len()),
+    };
+    record(root, &label, journal_changes);
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn list_hash_remap_backups(path: String) -> Result<Vec<RemapBackup>, AppError> {
+    let backups_dir = state_dir(Path::new(&path)).join(BACKUP_DIR_NAME);
+
+    let Ok(entries) = fs::read_dir(&backups_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut backups = entries
+        .flatten()
+        .filter_map(|entry| read_manifest(&entry.path()).ok())
+        .collect::<Vec<_>>();
+    backups.sort_by_key(|backup| Reverse(backup.created_at));
+
+    Ok(backups)
+}
+
+/// Restores the inis saved by a remap run, for one mod (`mod_id`) or all of them.
+/// Returns the number of restored files.
+#[tauri::command]
+pub async fn undo_hash_remap(
+    path: String,
+    backup_id: String,
+    mod_id: Option<String>,
+) -> Result<usize, AppError> {
+    let root = Path::new(&path);
+    let backup_dir = state_dir(root).join(BACKUP_DIR_NAME).join(&backup_id);
+
+    if backup_id.contains(['/', '\\', '.']) || !backup_dir.is_dir() {
+        return Err(AppError::not_found(format!(
+            "Hash remap backup {} not found",
+            backup_id
+        )));
+    }
+
+    let mut backup = read_manifest(&backup_dir)?;
+    let mods = load_library(root).await?;
+    let mut restored = Vec::new();
+    let mut journal_changes = Vec::new();
+
+    // Whatever was restored before a failure still goes into the journal
+    let result = backup
+        .mods
+        .iter()
+        .filter(|backed_up| mod_id.as_ref().is_none_or(|id| id == &backed_up.mod_id))
+        .try_for_each(|backed_up| {
+            let mod_data = mods
+                .iter()
+                .find(|mod_data| mod_data.id == backed_up.mod_id)
+                .ok_or_else(|| {
+                    AppError::not_found(format!(
+                        "Mod {} is no longer in the library",
+                        backed_up.mod_name
+                    ))
+                    .with_mod_id(&backed_up.mod_id)
+                })?;
+
+            restored.push(backed_up.mod_name.clone());
+            restore_mod(&backup_dir, backed_up, mod_data, &mut journal_changes)
+        });
+
+    let label = match restored.as_slice() {
+        [mod_name] => format!("Undo hash remap of {}", mod_name),
+        restored => format!("Undo hash remap of {} mods", restored.len()),
+    };
+    let restored_files = journal_changes.len();
+    record(root, &label, journal_changes);
+    result?;
+
+    backup
+        .mods
+        .retain(|backed_up| mod_id.as_ref().is_some_and(|id| id != &backed_up.mod_id));
+
+    if backup.mods.is_empty() {
+        fs::remove_dir_all(&backup_dir)
+            .map_err(|e| AppError::io("Failed to remove hash remap backup", e))?;
+    } else {
+        write_manifest(&backup_dir, &backup)?;
+    }
+
+    Ok(restored_files)
+}
+
+// Puts the backed up inis back, adding each one to `changes` for the journal
+fn restore_mod(
+    backup_dir: &Path,
+    backed_up: &BackedUpMod,
+    mod_data: &Mod,
+    changes: &mut Vec<FsChange>,
+) -> Result<(), AppError> {
+    for (index, file) in backed_up.files.iter().enumerate() {
+        if file.split(['/', '\\']).any(|part| part == "..") {
+            return Err(AppError::invalid_data(format!(
+                "Refusing to restore {} outside the mod folder",
+                file
+            )));
+        }
+
+        let ini_path = Path::new(&mod_data.path).join(file);
+        let original = fs::read_to_string(backup_file(backup_dir, &backed_up.mod_id, index))
+            .map_err(|e| AppError::io(format!("Failed to read the backup of {}", file), e))?;
+        let before = read_text(&ini_path);
+        replace_atomic(&ini_path, &original)?;
+        changes.push(FsChange::Write {
+            path: ini_path,
+            before,
+            after: Some(original),
+        });
+    }
+
+    println!(
+        "[hash-remap] Restored {} inis of {}",
+        backed_up.files.len(),
+        mod_data.name
+    );
+    Ok(())
+}
+
+/// Bundled and local tables merged into one `old -> latest` lookup, keys lower-cased.
+/// A local entry mapping a hash to itself cancels a bundled remap.
+pub fn load_remaps(root: &Path) -> Result<HashMap<String, String>, AppError> {
+    let bundled = serde_json::from_str::<RemapTable>(BUNDLED_TABLE)
+        .map_err(|e| AppError::invalid_data(format!("Bundled hash remap table is invalid: {e}")))?;
+
+    let local_path = state_file(root, LOCAL_TABLE_NAME)?;
+    let local = match fs::read_to_string(&local_path) {
+        Ok(content) => serde_json::from_str::<RemapTable>(&content)
+            .map_err(|e| AppError::invalid_data(format!("Invalid {}: {e}", LOCAL_TABLE_NAME)))?,
+        Err(_) => RemapTable::default(),
+    };
+
+    let mut direct = HashMap::new();
+    for table in [bundled, local] {
+        if table.version > TABLE_VERSION {
+            return Err(AppError::invalid_data(format!(
+                "Hash remap table version {} is newer than supported version {}",
+                table.version, TABLE_VERSION
+            )));
+        }
+
+        for patch in table.patches {
+            for (old, new) in patch.hashes {
+                if !is_hash(&old) || !is_hash(&new) {
+                    eprintln!(
+                        "[hash-remap] Skipping invalid remap {} -> {} ({})",
+                        old, new, patch.game_version
+                    );
+                    continue;
+                }
+                direct.insert(old.to_ascii_lowercase(), new.to_ascii_lowercase());
+            }
+        }
+    }
+
+    Ok(direct
+        .keys()
+        .filter_map(|old| {
+            let latest = resolve(&direct, old);
+            (&latest != old).then(|| (old.clone(), latest))
+        })
+        .collect())
+}
+
+// Follows `a -> b -> c` chains, stopping on cycles
+fn resolve(direct: &HashMap<String, String>, hash: &str) -> String {
+    let mut current = hash.to_string();
+
+    for _ in 0..direct.len() {
+        match direct.get(&current) {
+            Some(next) if next != &current && next != hash => current = next.clone(),
+            _ => break,
+        }
+    }
+
+    current
+}
+
+fn is_hash(value: &str) -> bool {
+    !value.is_empty() && value.len() <= 16 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Replaces every remapped `hash =` value, keeping the rest of each line untouched.
+pub fn rewrite_hashes(
+    content: &str,
+    file: &str,
+    remaps: &HashMap<String, String>,
+) -> (String, Vec<HashChange>) {
+    let mut output = String::with_capacity(content.len());
+    let mut changes = Vec::new();
+    let mut section = String::new();
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') {
+            section = trimmed
+                .find(']')
+                .map(|end| trimmed[1..end].trim().to_string())
+                .unwrap_or_default();
+        } else if let Some((name, value)) = line.split_once('=') {
+            let old = value.trim();
+
+            if name.trim().eq_ignore_ascii_case("hash") && !name.trim_start().starts_with(';') {
+                if let Some(new) = remaps.get(&old.to_ascii_lowercase()) {
+                    let start = name.len() + 1 + (value.len() - value.trim_start().len());
+                    output.push_str(&line[..start]);
+                    output.push_str(new);
+                    output.push_str(&line[start + old.len()..]);
+
+                    changes.push(HashChange {
+                        file: file.to_string(),
+                        line: index + 1,
+                        section: section.clone(),
+                        old: old.to_string(),
+                        new: new.clone(),
+                    });
+                    continue;
+                }
+            }
+        }
+
+        output.push_str(line);
+    }
+
+    (output, changes)
+}
+
+// `<mod id>-<n>.ini.bak`, the `.bak` keeps 3DMigoto from loading backups in the library
+fn backup_file(backup_dir: &Path, mod_id: &str, index: usize) -> std::path::PathBuf {
+    backup_dir.join(format!("{}-{}.ini.bak", mod_id, index))
+}
+
+fn read_manifest(backup_dir: &Path) -> Result<RemapBackup, AppError> {
+    let content = fs::read_to_string(backup_dir.join(MANIFEST_NAME))
+        .map_err(|e| AppError::io("Failed to read hash remap backup", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::invalid_data(format!("Invalid hash remap backup: {e}")))
+}
+
+fn write_manifest(backup_dir: &Path, backup: &RemapBackup) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(backup)?;
+    write_atomic(&backup_dir.join(MANIFEST_NAME), json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_remapped_hash_lines_only() {
+        let remaps = HashMap::from([("1a2b3c4d".to_string(), "deadbeef".to_string())]);
+        let content = "[TextureOverrideBody]\r\nhash = 1A2B3C4D  \r\n\r\n\
+                       [TextureOverrideHead]\r\nhash = 00000000\r\n";
+
+        let (rewritten, changes) = rewrite_hashes(content, "Skin.ini", &remaps);
+
+        assert_eq!(
+            rewritten,
+            "[TextureOverrideBody]\r\nhash = deadbeef  \r\n\r\n\
+             [TextureOverrideHead]\r\nhash = 00000000\r\n"
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].line, 2);
+        assert_eq!(changes[0].section, "TextureOverrideBody");
+    }
+
+    #[test]
+    fn follows_remap_chains_across_patches() {
+        let direct = HashMap::from([
+            ("aaaa0000".to_string(), "bbbb0000".to_string()),
+            ("bbbb0000".to_string(), "cccc0000".to_string()),
+            ("cccc0000".to_string(), "aaaa0000".to_string()),
+        ]);
+
+        assert_eq!(resolve(&direct, "aaaa0000"), "cccc0000");
+        assert_eq!(resolve(&direct, "dddd0000"), "dddd0000");
+    }
+}
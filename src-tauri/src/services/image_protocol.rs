@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::services::index::{modified_millis, OpenLibrary};
+use crate::services::mod_json::load_mod_json;
+use crate::services::mod_service::Mod;
+use crate::services::preview::THUMBNAIL_FILE_NAME;
+use crate::services::storage::state_dir;
+use crate::services::texture::{resized_copy, THUMBNAIL_DIR_NAME};
+
+/// Serves `modimg://localhost/<mod id>/thumbnail?size=256` and
+/// `modimg://localhost/<mod id>/gallery/<n>` (`http://modimg.localhost/...` on Windows).
+pub const SCHEME: &str = "modimg";
+
+// Requested sizes are rounded up to one of these so the cache stays small
+const SIZES: [u32; 5] = [64, 128, 256, 512, 1024];
+
+/// Maps mod ids to their folders, refreshed whenever the library is listed.
+#[derive(Default)]
+pub struct ModImageServer {
+    mod_dirs: Mutex<HashMap<String, PathBuf>>,
+}
+
+type ServeError = (StatusCode, String);
+
+pub fn handle_request<R: Runtime>(
+    app: &AppHandle<R>,
+    request: &Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    match serve(app, request) {
+        Ok(response) => response,
+        Err((status, message)) => {
+            eprintln!("[modimg] {} {}: {}", status, request.uri(), message);
+            Response::builder()
+                .status(status)
+                .body(message.into_bytes())
+                .unwrap_or_default()
+        }
+    }
+}
+
+fn serve<R: Runtime>(
+    app: &AppHandle<R>,
+    request: &Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, ServeError> {
+    let root = app
+        .state::<OpenLibrary>()
+        .root()
+        .ok_or((StatusCode::FORBIDDEN, "No Mods folder is open".to_string()))?;
+
+    // The frontend may encode the whole path as one segment
+    let path = request.uri().path().replace("%2F", "/").replace("%2f", "/");
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let size = request.uri().query().and_then(requested_size);
+
+    let (mod_id, source, cache_name) = match segments.as_slice() {
+        [mod_id, "thumbnail"] => {
+            let mod_dir = app.state::<ModImageServer>().mod_dir(mod_id)?;
+            (
+                *mod_id,
+                thumbnail_source(&root, mod_id, &mod_dir)?,
+                "".to_string(),
+            )
+        }
+        [mod_id, "gallery", index] => {
+            let mod_dir = app.state::<ModImageServer>().mod_dir(mod_id)?;
+            let index = index
+                .parse::<usize>()
+                .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid gallery index".to_string()))?;
+            let file = load_mod_json(&mod_dir)
+                .gallery
+                .get(index)
+                .cloned()
+                .ok_or((StatusCode::NOT_FOUND, "No such gallery image".to_string()))?;
+            (*mod_id, mod_dir.join(file), format!("gallery{}-", index))
+        }
+        _ => return Err((StatusCode::NOT_FOUND, "Unknown image".to_string())),
+    };
+
+    // Never serve anything outside the Mods folder, whatever the mod.json says
+    let canonical_root = root.canonicalize().map_err(not_found)?;
+    let source = source.canonicalize().map_err(not_found)?;
+    if !source.starts_with(&canonical_root) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Image is outside the Mods folder".to_string(),
+        ));
+    }
+
+    let metadata = fs::metadata(&source).map_err(not_found)?;
+    let etag = format!(
+        "\"{:x}-{:x}-{}\"",
+        modified_millis(&metadata),
+        metadata.len(),
+        size.unwrap_or(0)
+    );
+
+    let not_modified = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == etag.as_bytes());
+    if not_modified {
+        return Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, &etag)
+            .body(Vec::new())
+            .map_err(internal);
+    }
+
+    let (file, content_type) = match size {
+        Some(size) => {
+            let name = format!("{}-{}{}.png", mod_id, cache_name, size);
+            let resized = resized_copy(&root, &name, &source, size).ok_or((
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Image could not be decoded".to_string(),
+            ))?;
+            (resized, "image/png")
+        }
+        None => {
+            let content_type = content_type(&source);
+            (source, content_type)
+        }
+    };
+
+    let bytes = fs::read(&file).map_err(not_found)?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        // Revalidate every time, the ETag makes that a cheap 304
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ETAG, &etag)
+        .body(bytes)
+        .map_err(internal)
+}
+
+impl ModImageServer {
+    pub fn refresh(&self, mods: &[Mod]) {
+        *self.mod_dirs.lock().unwrap() = mods
+            .iter()
+            .map(|mod_data| (mod_data.id.clone(), PathBuf::from(&mod_data.path)))
+            .collect();
+    }
+
+    fn mod_dir(&self, mod_id: &str) -> Result<PathBuf, ServeError> {
+        self.mod_dirs
+            .lock()
+            .unwrap()
+            .get(mod_id)
+            .filter(|dir| dir.is_dir())
+            .cloned()
+            .ok_or((StatusCode::NOT_FOUND, format!("Unknown mod {}", mod_id)))
+    }
+}
+
+// thumbnail.png, or the thumbnail decoded from the mod's textures
+fn thumbnail_source(root: &Path, mod_id: &str, mod_dir: &Path) -> Result<PathBuf, ServeError> {
+    let thumbnail = mod_dir.join(THUMBNAIL_FILE_NAME);
+    if thumbnail.is_file() {
+        return Ok(thumbnail);
+    }
+
+    let fallback = state_dir(root)
+        .join(THUMBNAIL_DIR_NAME)
+        .join(format!("{}-texture.png", mod_id));
+    if fallback.is_file() {
+        return Ok(fallback);
+    }
+
+    Err((StatusCode::NOT_FOUND, "Mod has no thumbnail".to_string()))
+}
+
+fn requested_size(query: &str) -> Option<u32> {
+    let size = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "size")
+        .and_then(|(_, value)| value.parse::<u32>().ok())?;
+
+    Some(
+        SIZES
+            .into_iter()
+            .find(|known| *known >= size)
+            .unwrap_or(SIZES[SIZES.len() - 1]),
+    )
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        _ => "image/png",
+    }
+}
+
+fn not_found(error: std::io::Error) -> ServeError {
+    (StatusCode::NOT_FOUND, error.to_string())
+}
+
+fn internal(error: tauri::http::Error) -> ServeError {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_requested_sizes_up_to_cached_sizes() {
+        assert_eq!(requested_size("size=256"), Some(256));
+        assert_eq!(requested_size("v=2&size=300"), Some(512));
+        assert_eq!(requested_size("size=9000"), Some(1024));
+        assert_eq!(requested_size("size=big"), None);
+    }
+}
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::services::category::Category;
+use crate::services::discovery::discover_mods;
+use crate::services::error::AppError;
+use crate::services::image_protocol::ModImageServer;
+use crate::services::ini::find_ini_files;
+use crate::services::mod_service::{load_auto_match_categories, read_mod, Mod};
+use crate::services::search::refresh_search_index;
+use crate::services::storage::{replace_atomic, state_file};
+
+const INDEX_FILE_NAME: &str = "index.json";
+
+// Bump whenever `Mod` or the fingerprint changes shape so stale indexes are rebuilt.
+const INDEX_VERSION: u32 = 8;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprint {
+    // Latest modification time (ms) of the folder and the files it is parsed from
+    pub modified: u64,
+    // Combined size of those files
+    pub size: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub fingerprint: Fingerprint,
+    pub mod_data: Mod,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryIndex {
+    pub version: u32,
+    // Keyed by the absolute mod folder path
+    pub entries: HashMap<String, IndexEntry>,
+}
+
+/// Mods folder the app last listed, registered with `.manage()` for code that isn't handed
+/// the path, such as the image protocol.
+#[derive(Default)]
+pub struct OpenLibrary {
+    root: Mutex<Option<PathBuf>>,
+}
+
+impl OpenLibrary {
+    pub fn root(&self) -> Option<PathBuf> {
+        self.root.lock().unwrap().clone()
+    }
+}
+
+/// Records `root` as the open library after it was listed and refreshes the state derived
+/// from its mods.
+pub fn library_loaded<R: Runtime>(app: &AppHandle<R>, root: &Path, mods: &[Mod]) {
+    *app.state::<OpenLibrary>().root.lock().unwrap() = Some(root.to_path_buf());
+    app.state::<ModImageServer>().refresh(mods);
+
+    // Reading readmes and ini comments of new mods can take a while
+    let root = root.to_path_buf();
+    let mods = mods.to_vec();
+    tauri::async_runtime::spawn_blocking(move || refresh_search_index(&root, &mods));
+}
+
+/// Lists the library, re-reading only mod folders whose fingerprint changed since the last scan.
+pub async fn load_library(root: &Path) -> Result<Vec<Mod>, AppError> {
+    let mut index = read_index(root);
+    let locations = discover_mods(root)?;
+
+    // Character scraping is only needed when a folder actually has to be re-read.
+    let mut categories: Option<Vec<Category>> = None;
+    let mut entries = HashMap::with_capacity(locations.len());
+    let mut refreshed = 0;
+
+    for location in locations {
+        let key = location.path.to_string_lossy().to_string();
+        let current = fingerprint(&location.path);
+
+        let entry = match index.entries.remove(&key) {
+            Some(entry)
+                if entry.fingerprint == current && entry.mod_data.group == location.group =>
+            {
+                entry
+            }
+            _ => {
+                if categories.is_none() {
+                    categories = Some(load_auto_match_categories().await);
+                }
+
+                let mod_data = read_mod(
+                    &location.path,
+                    location.group,
+                    categories.as_deref().unwrap_or_default(),
+                );
+                refreshed += 1;
+
+                // Reading may have written a fresh id into mod.json, so fingerprint afterwards.
+                IndexEntry {
+                    fingerprint: fingerprint(&location.path),
+                    mod_data,
+                }
+            }
+        };
+
+        entries.insert(key, entry);
+    }
+
+    let removed = index.entries.len();
+    index.entries = entries;
+
+    if refreshed > 0 || removed > 0 || index.version != INDEX_VERSION {
+        println!(
+            "[index] Refreshed {} mods, dropped {} stale entries",
+            refreshed, removed
+        );
+        index.version = INDEX_VERSION;
+        if let Err(error) = write_index(root, &index) {
+            eprintln!("[index] Failed to save library index: {}", error);
+        }
+    }
+
+    let mut mods = index
+        .entries
+        .into_values()
+        .map(|entry| entry.mod_data)
+        .collect::<Vec<_>>();
+    mods.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(mods)
+}
+
+#[tauri::command]
+pub async fn rebuild_library_index(path: String) -> Result<Vec<Mod>, AppError> {
+    let dir = Path::new(&path);
+
+    if !dir.exists() || !dir.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let index_path = state_file(dir, INDEX_FILE_NAME)?;
+    if index_path.exists() {
+        fs::remove_file(&index_path)
+            .map_err(|e| AppError::io("Failed to remove library index", e))?;
+    }
+
+    load_library(dir).await
+}
+
+pub fn read_index(root: &Path) -> LibraryIndex {
+    let Ok(index_path) = state_file(root, INDEX_FILE_NAME) else {
+        return LibraryIndex::default();
+    };
+
+    let Ok(content) = fs::read_to_string(&index_path) else {
+        return LibraryIndex::default();
+    };
+
+    // The index is only a cache, anything unreadable is simply rebuilt.
+    match serde_json::from_str::<LibraryIndex>(&content) {
+        Ok(index) if index.version == INDEX_VERSION => index,
+        _ => LibraryIndex::default(),
+    }
+}
+
+fn write_index(root: &Path, index: &LibraryIndex) -> Result<(), AppError> {
+    let index_path = state_file(root, INDEX_FILE_NAME)?;
+    let json = serde_json::to_string(index)?;
+    // A cache needs no `.bak`, but a truncated one would mean rescanning every mod
+    replace_atomic(&index_path, json)
+}
+
+pub fn fingerprint(dir: &Path) -> Fingerprint {
+    let mut fingerprint = Fingerprint {
+        modified: fs::metadata(dir)
+            .map(|metadata| modified_millis(&metadata))
+            .unwrap_or(0),
+        ..Default::default()
+    };
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return fingerprint;
+    };
+
+    // Direct files plus inis in subfolders, since those feed the parsed ini summary
+    let nested_inis = find_ini_files(dir)
+        .into_iter()
+        .filter(|path| path.parent() != Some(dir));
+    let files = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .chain(nested_inis);
+
+    for file in files {
+        let Ok(metadata) = fs::metadata(&file) else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+
+        fingerprint.files += 1;
+        fingerprint.size += metadata.len();
+        fingerprint.modified = fingerprint.modified.max(modified_millis(&metadata));
+    }
+
+    fingerprint
+}
+
+pub fn modified_millis(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
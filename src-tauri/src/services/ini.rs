@@ -0,0 +1,740 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::services::discovery::is_ini_file;
+use crate::services::enable_state::{
+    is_disabled_name, name_for_state, parse_name, strip_disabled_prefix, DisabledPrefix,
+};
+use crate::services::error::AppError;
+use crate::services::journal::{record, FsChange};
+use crate::services::storage::{find_library_root, resolve_mod_file};
+
+// Prefix written when the manager disables a single ini
+const DISABLED_INI_PREFIX: DisabledPrefix = DisabledPrefix::Xxmi;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IniEntry {
+    // Everything left of the first '=', e.g. `hash` or `global persist $swapvar`
+    pub key: String,
+    pub value: Option<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IniSection {
+    pub name: String,
+    pub line: usize,
+    pub entries: Vec<IniEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IniError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IniDocument {
+    pub namespace: Option<String>,
+    pub sections: Vec<IniSection>,
+    pub errors: Vec<IniError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideHash {
+    pub hash: String,
+    pub section: String,
+    // Ini file relative to the mod folder
+    pub file: String,
+    pub match_first_index: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBinding {
+    pub section: String,
+    pub file: String,
+    // Raw `key =` value as written in the ini
+    pub key: String,
+    pub combination: KeyCombination,
+    // `type =` of the section: activate (default), hold, toggle or cycle
+    pub kind: String,
+    // Variables assigned when the key fires, e.g. `$swapvar`
+    pub variables: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyCombination {
+    // Upper-case key name without the `VK_` prefix, e.g. `F5` or `1`
+    pub key: String,
+    // Sorted required modifiers: alt, ctrl, shift, win
+    pub modifiers: Vec<String>,
+    // Modifiers that must not be held (`NO_CTRL`), or all of them for `NO_MODIFIERS`
+    pub excluded: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceRef {
+    pub section: String,
+    pub file: String,
+    // Referenced file resolved relative to the mod folder
+    pub path: String,
+}
+
+/// An ini shipped with the mod and whether 3DMigoto currently loads it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IniFileState {
+    // Current path relative to the mod folder, including any DISABLED prefix
+    pub file: String,
+    // File name without the DISABLED prefix
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// Structured view of every ini that 3DMigoto would load from a mod folder.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IniSummary {
+    pub files: Vec<String>,
+    pub namespaces: Vec<String>,
+    pub hashes: Vec<OverrideHash>,
+    pub keybinds: Vec<KeyBinding>,
+    pub resources: Vec<ResourceRef>,
+    pub variables: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Parses 3DMigoto ini syntax. Never fails, problems are collected in `errors`.
+pub fn parse_ini(content: &str) -> IniDocument {
+    let mut document = IniDocument::default();
+    let mut current: Option<IniSection> = None;
+    let mut open_ifs: Vec<usize> = Vec::new();
+
+    for (index, raw_line) in content.trim_start_matches('\u{feff}').lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            close_section(&mut document, &mut current, &mut open_ifs);
+
+            match line.find(']') {
+                Some(end) => {
+                    current = Some(IniSection {
+                        name: line[1..end].trim().to_string(),
+                        line: line_number,
+                        entries: Vec::new(),
+                    });
+                }
+                None => document.errors.push(IniError {
+                    line: line_number,
+                    message: format!("Unterminated section header '{}'", line),
+                }),
+            }
+            continue;
+        }
+
+        let lower = line.to_ascii_lowercase();
+        let keyword = lower.split_whitespace().next().unwrap_or("");
+
+        // `else if` continues the current block, only `if` opens a new one
+        let unmatched = match keyword {
+            "if" => {
+                open_ifs.push(line_number);
+                false
+            }
+            "elif" | "else" => open_ifs.is_empty(),
+            "endif" => open_ifs.pop().is_none(),
+            _ => false,
+        };
+
+        if unmatched {
+            document.errors.push(IniError {
+                line: line_number,
+                message: format!("'{}' without a matching 'if'", keyword),
+            });
+        }
+
+        let entry = if matches!(keyword, "if" | "elif" | "else" | "endif") {
+            IniEntry {
+                key: line.to_string(),
+                value: None,
+                line: line_number,
+            }
+        } else {
+            match line.split_once('=') {
+                Some((key, value)) => IniEntry {
+                    key: key.trim().to_string(),
+                    value: Some(value.trim().to_string()),
+                    line: line_number,
+                },
+                None => IniEntry {
+                    key: line.to_string(),
+                    value: None,
+                    line: line_number,
+                },
+            }
+        };
+
+        match current.as_mut() {
+            Some(section) => section.entries.push(entry),
+            None if entry.key.eq_ignore_ascii_case("namespace") => {
+                document.namespace = entry.value;
+            }
+            None => document.errors.push(IniError {
+                line: line_number,
+                message: format!("'{}' appears before any section", line),
+            }),
+        }
+    }
+
+    close_section(&mut document, &mut current, &mut open_ifs);
+    document
+}
+
+fn close_section(
+    document: &mut IniDocument,
+    current: &mut Option<IniSection>,
+    open_ifs: &mut Vec<usize>,
+) {
+    if let Some(section) = current.take() {
+        for line in open_ifs.drain(..) {
+            document.errors.push(IniError {
+                line,
+                message: format!("'if' is never closed in section [{}]", section.name),
+            });
+        }
+        document.sections.push(section);
+    }
+}
+
+impl IniSection {
+    /// First value for `key`, compared case-insensitively like 3DMigoto does.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.key.eq_ignore_ascii_case(key))
+            .and_then(|entry| entry.value.as_deref())
+    }
+
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        starts_with_ignore_case(&self.name, prefix)
+    }
+}
+
+/// Every ini file 3DMigoto would load from `mod_dir`, skipping `DISABLED*` files and folders.
+pub fn find_ini_files(mod_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_ini_files(mod_dir, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_ini_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if is_excluded(&path) {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_ini_files(&path, files);
+        } else if is_ini_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+pub fn is_excluded(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(is_disabled_name)
+        .unwrap_or(false)
+}
+
+/// Every ini in the mod folder, enabled or not. Inis inside `DISABLED*` sub-folders are
+/// left out since toggling the file alone would not make 3DMigoto load them.
+pub fn list_ini_files(mod_dir: &Path) -> Vec<IniFileState> {
+    let mut files = Vec::new();
+    collect_ini_states(mod_dir, mod_dir, &mut files);
+    files.sort_by(|a, b| a.file.cmp(&b.file));
+    files
+}
+
+fn collect_ini_states(mod_dir: &Path, dir: &Path, files: &mut Vec<IniFileState>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            if !is_excluded(&path) {
+                collect_ini_states(mod_dir, &path, files);
+            }
+        } else if is_ini_file(&path) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let name = strip_disabled_prefix(&file_name).unwrap_or(&file_name);
+
+            files.push(IniFileState {
+                file: relative_to(mod_dir, &path),
+                name: name.to_string(),
+                enabled: !is_excluded(&path),
+            });
+        }
+    }
+}
+
+/// Turns a single ini of a mod on or off by renaming it with the XXMI `DISABLED` prefix.
+#[tauri::command]
+pub fn set_ini_enabled(
+    path: String,
+    file: String,
+    enabled: bool,
+) -> Result<Vec<IniFileState>, AppError> {
+    let mod_dir = Path::new(&path);
+    let ini_path = resolve_mod_file(mod_dir, &file, "Ini file")?;
+
+    let file_name = ini_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| AppError::invalid_input("Invalid ini file name").with_path(&ini_path))?;
+
+    if is_disabled_name(file_name) != enabled {
+        // Already in the requested state
+        return Ok(list_ini_files(mod_dir));
+    }
+    let new_name = name_for_state(file_name, enabled, DISABLED_INI_PREFIX);
+
+    let new_path = ini_path.with_file_name(&new_name);
+    if new_path.exists() {
+        return Err(AppError::already_exists(format!(
+            "{} already exists next to {}",
+            new_name, file_name
+        ))
+        .with_path(&new_path));
+    }
+
+    let root = find_library_root(mod_dir)?;
+    fs::rename(&ini_path, &new_path).map_err(|e| AppError::io("Failed to rename ini", e))?;
+    record(
+        &root,
+        &format!(
+            "{} {}",
+            if enabled { "Enable" } else { "Disable" },
+            parse_name(file_name).1
+        ),
+        vec![FsChange::Move {
+            from: ini_path,
+            to: new_path,
+        }],
+    );
+
+    Ok(list_ini_files(mod_dir))
+}
+
+pub fn starts_with_ignore_case(value: &str, prefix: &str) -> bool {
+    value
+        .get(..prefix.len())
+        .map(|start| start.eq_ignore_ascii_case(prefix))
+        .unwrap_or(false)
+}
+
+pub fn read_ini(path: &Path) -> Result<IniDocument, AppError> {
+    let bytes = fs::read(path)
+        .map_err(|e| AppError::io(format!("Failed to read {}", path.display()), e))?;
+    Ok(parse_ini(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Parses the inis of a mod folder, following `[Include]` sections, into one summary.
+pub fn summarize_mod(mod_dir: &Path) -> IniSummary {
+    let mut summary = IniSummary::default();
+    let mut visited = HashSet::new();
+    let mut queue = find_ini_files(mod_dir);
+
+    while let Some(ini_path) = queue.pop() {
+        if !visited.insert(normalize(&ini_path)) {
+            continue;
+        }
+
+        let file = relative_to(mod_dir, &ini_path);
+        let document = match read_ini(&ini_path) {
+            Ok(document) => document,
+            Err(error) => {
+                summary.errors.push(error.message);
+                continue;
+            }
+        };
+
+        let ini_dir = ini_path.parent().unwrap_or(mod_dir);
+        queue.extend(included_files(&document, ini_dir));
+        add_document(&mut summary, &document, mod_dir, ini_dir, &file);
+        summary.files.push(file);
+    }
+
+    summary.files.sort();
+    summary.variables.sort();
+    summary.variables.dedup();
+    summary
+}
+
+fn add_document(
+    summary: &mut IniSummary,
+    document: &IniDocument,
+    mod_dir: &Path,
+    ini_dir: &Path,
+    file: &str,
+) {
+    if let Some(namespace) = &document.namespace {
+        summary.namespaces.push(namespace.clone());
+    }
+
+    for error in &document.errors {
+        summary
+            .errors
+            .push(format!("{}:{}: {}", file, error.line, error.message));
+    }
+
+    for section in &document.sections {
+        if let Some(hash) = section.get("hash") {
+            summary.hashes.push(OverrideHash {
+                hash: hash.to_ascii_lowercase(),
+                section: section.name.clone(),
+                file: file.to_string(),
+                match_first_index: section.get("match_first_index").map(str::to_string),
+            });
+        }
+
+        if section.has_prefix("Key") {
+            summary.keybinds.extend(key_bindings(section, file));
+        }
+
+        if section.has_prefix("Resource") {
+            if let Some(filename) = section.get("filename") {
+                summary.resources.push(ResourceRef {
+                    section: section.name.clone(),
+                    file: file.to_string(),
+                    path: relative_to(mod_dir, &ini_dir.join(filename.replace('\\', "/"))),
+                });
+            }
+        }
+
+        for entry in &section.entries {
+            if let Some(variable) = declared_variable(&entry.key) {
+                summary.variables.push(variable.to_string());
+            }
+        }
+    }
+}
+
+// A [Key] section may list several `key =` lines, each one is its own binding
+fn key_bindings(section: &IniSection, file: &str) -> Vec<KeyBinding> {
+    let kind = section
+        .get("type")
+        .map(|kind| kind.to_ascii_lowercase())
+        .unwrap_or_else(|| "activate".to_string());
+
+    let variables = section
+        .entries
+        .iter()
+        .filter(|entry| entry.value.is_some() && entry.key.starts_with('$'))
+        .map(|entry| entry.key.clone())
+        .collect::<Vec<_>>();
+
+    section
+        .entries
+        .iter()
+        .filter(|entry| entry.key.eq_ignore_ascii_case("key"))
+        .filter_map(|entry| entry.value.as_deref())
+        .map(|key| KeyBinding {
+            section: section.name.clone(),
+            file: file.to_string(),
+            key: key.to_string(),
+            combination: parse_key_combination(key),
+            kind: kind.clone(),
+            variables: variables.clone(),
+        })
+        .collect()
+}
+
+const MODIFIERS: [&str; 4] = ["alt", "ctrl", "shift", "win"];
+
+/// Parses 3DMigoto key syntax such as `VK_F5`, `ctrl 1` or `no_modifiers shift VK_UP`.
+pub fn parse_key_combination(value: &str) -> KeyCombination {
+    let mut combination = KeyCombination::default();
+
+    for token in value.split_whitespace() {
+        let lower = token.to_ascii_lowercase();
+
+        if lower == "no_modifiers" {
+            combination.excluded = MODIFIERS
+                .iter()
+                .map(|modifier| modifier.to_string())
+                .collect();
+        } else if let Some(modifier) = lower.strip_prefix("no_").and_then(modifier_name) {
+            combination.excluded.push(modifier.to_string());
+        } else if let Some(modifier) = modifier_name(&lower) {
+            combination.modifiers.push(modifier.to_string());
+        } else {
+            let upper = token.to_ascii_uppercase();
+            combination.key = upper.strip_prefix("VK_").unwrap_or(&upper).to_string();
+        }
+    }
+
+    for list in [&mut combination.modifiers, &mut combination.excluded] {
+        list.sort();
+        list.dedup();
+    }
+    let required = combination.modifiers.clone();
+    combination
+        .excluded
+        .retain(|modifier| !required.contains(modifier));
+
+    combination
+}
+
+fn modifier_name(token: &str) -> Option<&'static str> {
+    match token {
+        "ctrl" | "control" | "lctrl" | "rctrl" | "vk_control" | "vk_lcontrol" | "vk_rcontrol" => {
+            Some("ctrl")
+        }
+        "alt" | "lalt" | "ralt" | "menu" | "vk_menu" | "vk_lmenu" | "vk_rmenu" => Some("alt"),
+        "shift" | "lshift" | "rshift" | "vk_shift" | "vk_lshift" | "vk_rshift" => Some("shift"),
+        "win" | "lwin" | "rwin" | "vk_lwin" | "vk_rwin" => Some("win"),
+        _ => None,
+    }
+}
+
+impl KeyCombination {
+    /// Whether pressing one combination can also trigger the other. 3DMigoto ignores
+    /// modifiers that a binding does not mention, so `ctrl F5` and `alt F5` both fire on
+    /// `ctrl alt F5`, and `F5` fires on `ctrl F5` unless the binding excludes ctrl.
+    pub fn overlaps(&self, other: &KeyCombination) -> bool {
+        if self.key.is_empty() || !self.key.eq_ignore_ascii_case(&other.key) {
+            return false;
+        }
+
+        // Holding every modifier either binding requires triggers both, unless one of them
+        // excludes a modifier that is held
+        let excludes_held = |binding: &KeyCombination| {
+            binding.excluded.iter().any(|modifier| {
+                self.modifiers.contains(modifier) || other.modifiers.contains(modifier)
+            })
+        };
+
+        !excludes_held(self) && !excludes_held(other)
+    }
+
+    pub fn display(&self) -> String {
+        self.modifiers
+            .iter()
+            .cloned()
+            .chain(std::iter::once(self.key.clone()))
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+fn included_files(document: &IniDocument, ini_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    for section in document
+        .sections
+        .iter()
+        .filter(|section| section.has_prefix("Include"))
+    {
+        for entry in &section.entries {
+            let Some(value) = entry.value.as_deref() else {
+                continue;
+            };
+            let target = ini_dir.join(value.replace('\\', "/"));
+
+            if entry.key.eq_ignore_ascii_case("include") && target.is_file() {
+                files.push(target);
+            } else if entry.key.eq_ignore_ascii_case("include_recursive") && target.is_dir() {
+                files.extend(find_ini_files(&target));
+            }
+        }
+    }
+
+    files
+}
+
+// `global persist $swapvar = 0` and `$active = 1` both declare `$swapvar` / `$active`
+fn declared_variable(key: &str) -> Option<&str> {
+    key.split_whitespace()
+        .last()
+        .filter(|word| word.starts_with('$') && word.len() > 1)
+}
+
+/// Path of `path` relative to `base` with '/' separators, keeping `..` for files outside it.
+pub fn relative_to(base: &Path, path: &Path) -> String {
+    let base = normalize(base);
+    let path = normalize(path);
+
+    if let Ok(relative) = path.strip_prefix(&base) {
+        return join_components(relative);
+    }
+
+    let base_parts = base.components().collect::<Vec<_>>();
+    let path_parts = path.components().collect::<Vec<_>>();
+    let common = base_parts
+        .iter()
+        .zip(&path_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts = vec!["..".to_string(); base_parts.len() - common];
+    parts.extend(
+        path_parts[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+fn join_components(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Lexically resolves `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+namespace = JinhsiSkin
+; Constants
+[Constants]
+global persist $swapvar = 0
+
+[KeySwap]
+condition = $active == 1
+key = VK_F5
+type = cycle
+$swapvar = 0,1
+
+[TextureOverrideBody]
+hash = 1A2B3C4D
+match_first_index = 0
+if $swapvar == 0
+    ib = ResourceBodyIB
+else
+    ib = null
+endif
+
+[ResourceBodyIB]
+type = Buffer
+filename = Meshes\Body.ib
+"#;
+
+    #[test]
+    fn parses_sections_entries_and_namespace() {
+        let document = parse_ini(SAMPLE);
+
+        assert_eq!(document.namespace.as_deref(), Some("JinhsiSkin"));
+        assert!(document.errors.is_empty());
+        assert_eq!(document.sections.len(), 4);
+
+        let body = &document.sections[2];
+        assert_eq!(body.name, "TextureOverrideBody");
+        assert_eq!(body.get("HASH"), Some("1A2B3C4D"));
+        assert_eq!(body.entries[2].key, "if $swapvar == 0");
+        assert_eq!(body.entries[2].value, None);
+    }
+
+    #[test]
+    fn reports_unbalanced_conditionals() {
+        let document =
+            parse_ini("[TextureOverrideA]\nif $a == 1\nhash = 1\n[TextureOverrideB]\nendif\n");
+
+        assert_eq!(document.errors.len(), 2);
+        assert_eq!(document.errors[0].line, 2);
+        assert_eq!(document.errors[1].line, 5);
+    }
+
+    #[test]
+    fn summarizes_mod_folder_with_includes() {
+        let root = std::env::temp_dir().join(format!("wuwa-ini-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("Meshes")).unwrap();
+        fs::create_dir_all(root.join("DISABLED_Extras")).unwrap();
+        fs::write(root.join("Skin.ini"), SAMPLE).unwrap();
+        fs::write(
+            root.join("DISABLED_Extras").join("Extra.ini"),
+            "[TextureOverrideX]\nhash = ffff0000\n",
+        )
+        .unwrap();
+
+        let summary = summarize_mod(&root);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(summary.files, vec!["Skin.ini"]);
+        assert_eq!(summary.namespaces, vec!["JinhsiSkin"]);
+        assert_eq!(summary.hashes.len(), 1);
+        assert_eq!(summary.hashes[0].hash, "1a2b3c4d");
+        assert_eq!(summary.hashes[0].match_first_index.as_deref(), Some("0"));
+        assert_eq!(summary.keybinds[0].key, "VK_F5");
+        assert_eq!(summary.keybinds[0].kind, "cycle");
+        assert_eq!(summary.keybinds[0].variables, vec!["$swapvar"]);
+        assert_eq!(summary.resources[0].path, "Meshes/Body.ib");
+        assert_eq!(summary.variables, vec!["$swapvar"]);
+    }
+
+    #[test]
+    fn parses_key_combinations_and_overlaps() {
+        let plain = parse_key_combination("VK_F5");
+        let ctrl = parse_key_combination("ctrl F5");
+        let exclusive = parse_key_combination("no_modifiers vk_f5");
+
+        assert_eq!(ctrl.modifiers, vec!["ctrl"]);
+        assert_eq!(ctrl.display(), "ctrl+F5");
+        assert_eq!(exclusive.excluded, vec!["alt", "ctrl", "shift", "win"]);
+
+        assert!(plain.overlaps(&ctrl));
+        assert!(plain.overlaps(&exclusive));
+        assert!(!exclusive.overlaps(&ctrl));
+        assert!(ctrl.overlaps(&parse_key_combination("alt F5")));
+        assert!(!ctrl.overlaps(&parse_key_combination("no_ctrl alt F5")));
+        assert!(!ctrl.overlaps(&parse_key_combination("ctrl F6")));
+    }
+
+    #[test]
+    fn relative_paths_keep_parent_segments() {
+        assert_eq!(
+            relative_to(Path::new("/mods/a"), Path::new("/mods/a/../shared/lib.ini")),
+            "../shared/lib.ini"
+        );
+    }
+}
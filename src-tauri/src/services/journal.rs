@@ -0,0 +1,418 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::services::discovery::is_ini_file;
+use crate::services::error::AppError;
+use crate::services::mod_json::now_millis;
+use crate::services::mod_service::copy_dir_recursive;
+use crate::services::storage::{
+    read_recovering, replace_atomic, state_dir, state_file, write_atomic,
+};
+
+const JOURNAL_FILE_NAME: &str = "journal.json";
+// Folders created by an undone add or download are parked here so it can be redone, as are
+// replaced binary files such as thumbnail.png. The DISABLED prefix keeps 3DMigoto from
+// loading them. Deleted mods go to the trash instead.
+const STASH_DIR_NAME: &str = "DISABLED_journal";
+// Older entries are dropped, along with anything they stashed
+const MAX_ENTRIES: usize = 50;
+
+// Commands run concurrently, journal.json is read-modify-written under this lock
+static JOURNAL_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// One reversible filesystem change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FsChange {
+    // Renames, deletions (moved into the trash) and creations (moved out of the stash)
+    Move {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    // Text file contents, None when the file did not exist
+    Write {
+        path: PathBuf,
+        before: Option<String>,
+        after: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: String,
+    pub label: String,
+    // Unix millis
+    pub created_at: u64,
+    pub changes: Vec<FsChange>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Journal {
+    entries: Vec<JournalEntry>,
+    // Entries before this are applied, the ones from here on were undone and can be redone
+    position: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+    pub id: String,
+    pub label: String,
+    pub created_at: u64,
+    pub changes: usize,
+    pub undone: bool,
+}
+
+impl FsChange {
+    /// A write of `path`, given its contents before the change. Reads the new contents.
+    pub fn write(path: &Path, before: Option<String>) -> FsChange {
+        FsChange::Write {
+            path: path.to_path_buf(),
+            before,
+            after: read_text(path),
+        }
+    }
+
+    pub fn apply(&self) -> Result<(), AppError> {
+        match self {
+            FsChange::Move { from, to } => move_path(from, to),
+            FsChange::Write { path, after, .. } => write_text(path, after.as_deref()),
+        }
+    }
+
+    pub fn revert(&self) -> Result<(), AppError> {
+        match self {
+            FsChange::Move { from, to } => move_path(to, from),
+            FsChange::Write { path, before, .. } => write_text(path, before.as_deref()),
+        }
+    }
+}
+
+/// Newest first, undone entries included so the UI can offer redo.
+#[tauri::command]
+pub fn get_journal_history(path: String) -> Result<Vec<HistoryItem>, AppError> {
+    let root = Path::new(&path);
+    let _lock = JOURNAL_LOCK.lock().unwrap();
+    let journal = read_journal(root);
+
+    Ok(journal
+        .entries
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, entry)| HistoryItem {
+            id: entry.id.clone(),
+            label: entry.label.clone(),
+            created_at: entry.created_at,
+            changes: entry.changes.len(),
+            undone: index >= journal.position,
+        })
+        .collect())
+}
+
+/// Reverts the most recent applied entry and returns its label.
+#[tauri::command]
+pub fn undo_last(path: String) -> Result<String, AppError> {
+    let root = Path::new(&path);
+    let _lock = JOURNAL_LOCK.lock().unwrap();
+    let mut journal = read_journal(root);
+
+    if journal.position == 0 {
+        return Err(AppError::invalid_input("Nothing to undo"));
+    }
+
+    let entry = &journal.entries[journal.position - 1];
+    let changes = entry.changes.iter().rev().collect::<Vec<_>>();
+    run_all(&changes, FsChange::revert, FsChange::apply).map_err(|e| AppError {
+        message: format!("Could not undo '{}': {}", entry.label, e),
+        ..e
+    })?;
+
+    println!("[journal] Undid '{}'", entry.label);
+    let label = entry.label.clone();
+    journal.position -= 1;
+    write_journal(root, &journal)?;
+    Ok(label)
+}
+
+/// Re-applies the most recently undone entry and returns its label.
+#[tauri::command]
+pub fn redo(path: String) -> Result<String, AppError> {
+    let root = Path::new(&path);
+    let _lock = JOURNAL_LOCK.lock().unwrap();
+    let mut journal = read_journal(root);
+
+    let Some(entry) = journal.entries.get(journal.position) else {
+        return Err(AppError::invalid_input("Nothing to redo"));
+    };
+
+    let changes = entry.changes.iter().collect::<Vec<_>>();
+    run_all(&changes, FsChange::apply, FsChange::revert).map_err(|e| AppError {
+        message: format!("Could not redo '{}': {}", entry.label, e),
+        ..e
+    })?;
+
+    println!("[journal] Redid '{}'", entry.label);
+    let label = entry.label.clone();
+    journal.position += 1;
+    write_journal(root, &journal)?;
+    Ok(label)
+}
+
+/// Adds an entry for changes a command already made. Entries that were undone can no longer
+/// be redone afterwards. Failures are only logged, the change itself went through.
+pub fn record(root: &Path, label: &str, mut changes: Vec<FsChange>) {
+    // Saving a file with the same contents is not worth an undo step
+    changes.retain(
+        |change| !matches!(change, FsChange::Write { before, after, .. } if before == after),
+    );
+    if changes.is_empty() {
+        return;
+    }
+
+    let _lock = JOURNAL_LOCK.lock().unwrap();
+    let mut journal = read_journal(root);
+
+    let dropped = journal.entries.split_off(journal.position);
+    journal.entries.push(JournalEntry {
+        id: Uuid::new_v4().to_string(),
+        label: label.to_string(),
+        created_at: now_millis(),
+        changes,
+    });
+
+    let overflow = journal.entries.len().saturating_sub(MAX_ENTRIES);
+    let pruned = journal.entries.drain(..overflow).collect::<Vec<_>>();
+    journal.position = journal.entries.len();
+
+    for entry in dropped.iter().chain(&pruned) {
+        purge_stashed(root, entry);
+    }
+
+    if let Err(error) = write_journal(root, &journal) {
+        eprintln!("[journal] Failed to record '{}': {}", label, error);
+    }
+}
+
+/// Records a folder created at `path`: undoing it moves the folder into the stash.
+pub fn created(root: &Path, path: &Path) -> Result<FsChange, AppError> {
+    Ok(FsChange::Move {
+        from: stash_path(root)?,
+        to: path.to_path_buf(),
+    })
+}
+
+/// Moves the file at `path` into the stash before it is replaced, for files the journal can't
+/// keep as text. Together with [`created`] for the new file, undo puts the old one back.
+pub fn stash(root: &Path, path: &Path) -> Result<Option<FsChange>, AppError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let stashed = stash_path(root)?;
+    move_path(path, &stashed)?;
+    Ok(Some(FsChange::Move {
+        from: path.to_path_buf(),
+        to: stashed,
+    }))
+}
+
+pub fn read_text(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+fn stash_path(root: &Path) -> Result<PathBuf, AppError> {
+    let dir = state_dir(root).join(STASH_DIR_NAME);
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Failed to create {}", dir.display()), e))?;
+    Ok(dir.join(Uuid::new_v4().to_string()))
+}
+
+// Runs `step` over every change. If one fails, the ones already done are put back with
+// `undo_step` so the library is never left halfway.
+fn run_all(
+    changes: &[&FsChange],
+    step: fn(&FsChange) -> Result<(), AppError>,
+    undo_step: fn(&FsChange) -> Result<(), AppError>,
+) -> Result<(), AppError> {
+    for (done, change) in changes.iter().enumerate() {
+        if let Err(error) = step(change) {
+            for change in changes[..done].iter().rev() {
+                if let Err(undo_error) = undo_step(change) {
+                    eprintln!("[journal] Failed to restore {:?}: {}", change, undo_error);
+                }
+            }
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// Renames `from` to `to`, copying when they are on different drives.
+pub fn move_path(from: &Path, to: &Path) -> Result<(), AppError> {
+    if !from.exists() {
+        return Err(
+            AppError::not_found(format!("{} no longer exists", from.display())).with_path(from),
+        );
+    }
+    if to.exists() {
+        return Err(
+            AppError::already_exists(format!("{} already exists", to.display())).with_path(to),
+        );
+    }
+
+    if fs::rename(from, to).is_ok() {
+        touch(to);
+        return Ok(());
+    }
+
+    let copied = if from.is_dir() {
+        copy_dir_recursive(from, to)
+    } else {
+        fs::copy(from, to).map(|_| ())
+    };
+
+    copied
+        .and_then(|()| {
+            if from.is_dir() {
+                fs::remove_dir_all(from)
+            } else {
+                fs::remove_file(from)
+            }
+        })
+        .map_err(|e| {
+            AppError::io(format!("Failed to move {}", from.display()), e).with_path(from)
+        })?;
+    touch(to);
+    Ok(())
+}
+
+// Caches such as the grid thumbnails compare modification times, so a file moved back in
+// place must not look older than what it replaced
+fn touch(path: &Path) {
+    if path.is_file() {
+        if let Err(error) = File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+        {
+            eprintln!("[journal] Failed to touch {}: {}", path.display(), error);
+        }
+    }
+}
+
+fn write_text(path: &Path, contents: Option<&str>) -> Result<(), AppError> {
+    match contents {
+        // Mod inis keep their own `.bak` with the author's version
+        Some(contents) if is_ini_file(path) => replace_atomic(path, contents),
+        Some(contents) => write_atomic(path, contents),
+        None if path.exists() => fs::remove_file(path)
+            .map_err(|e| AppError::io(format!("Failed to remove {}", path.display()), e)),
+        None => Ok(()),
+    }
+}
+
+// Entries that can no longer be undone or redone don't need their stashed folders
+fn purge_stashed(root: &Path, entry: &JournalEntry) {
+    let stash_dir = state_dir(root).join(STASH_DIR_NAME);
+
+    for change in &entry.changes {
+        let FsChange::Move { from, to } = change else {
+            continue;
+        };
+        for path in [from, to] {
+            if path.starts_with(&stash_dir) && path.exists() {
+                let removed = if path.is_dir() {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                if let Err(error) = removed {
+                    eprintln!("[journal] Failed to purge {}: {}", path.display(), error);
+                }
+            }
+        }
+    }
+}
+
+fn read_journal(root: &Path) -> Journal {
+    let Ok(journal_path) = state_file(root, JOURNAL_FILE_NAME) else {
+        return Journal::default();
+    };
+
+    let content = read_recovering(&journal_path, |content| {
+        serde_json::from_str::<Journal>(content).is_ok()
+    });
+
+    match content {
+        Some(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("[journal] Ignoring unreadable journal.json: {}", e);
+            Journal::default()
+        }),
+        None => Journal::default(),
+    }
+}
+
+fn write_journal(root: &Path, journal: &Journal) -> Result<(), AppError> {
+    let journal_path = state_file(root, JOURNAL_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(journal)?;
+    write_atomic(&journal_path, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undoes_and_redoes_renames_and_writes() {
+        let root = std::env::temp_dir().join(format!("wuwa-journal-{}", uuid::Uuid::new_v4()));
+        let root_path = root.to_string_lossy().to_string();
+        fs::create_dir_all(root.join("Alpha")).unwrap();
+        fs::create_dir_all(root.join("Beta")).unwrap();
+        fs::write(root.join("Alpha").join("mod.json"), "old").unwrap();
+
+        let before = read_text(&root.join("Alpha").join("mod.json"));
+        fs::write(root.join("Alpha").join("mod.json"), "new").unwrap();
+        let write = FsChange::write(&root.join("Alpha").join("mod.json"), before);
+        fs::rename(root.join("Alpha"), root.join("disabled Alpha")).unwrap();
+        let rename = FsChange::Move {
+            from: root.join("Alpha"),
+            to: root.join("disabled Alpha"),
+        };
+        record(&root, "Edit Alpha", vec![write, rename]);
+        fs::rename(root.join("Beta"), root.join("Gamma")).unwrap();
+        let rename = FsChange::Move {
+            from: root.join("Beta"),
+            to: root.join("Gamma"),
+        };
+        record(&root, "Rename Beta", vec![rename]);
+
+        assert_eq!(undo_last(root_path.clone()).unwrap(), "Rename Beta");
+        assert_eq!(undo_last(root_path.clone()).unwrap(), "Edit Alpha");
+        assert!(root.join("Beta").is_dir());
+        assert_eq!(
+            fs::read_to_string(root.join("Alpha").join("mod.json")).unwrap(),
+            "old"
+        );
+        assert!(undo_last(root_path.clone()).is_err());
+
+        assert_eq!(redo(root_path.clone()).unwrap(), "Edit Alpha");
+        assert!(root.join("disabled Alpha").is_dir());
+        let history = get_journal_history(root_path.clone()).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            history
+                .iter()
+                .map(|item| (item.label.as_str(), item.undone))
+                .collect::<Vec<_>>(),
+            vec![("Rename Beta", true), ("Edit Alpha", false)]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::error::AppError;
+use crate::services::index::load_library;
+use crate::services::ini::{parse_key_combination, summarize_mod, KeyBinding};
+use crate::services::journal::{read_text, record, FsChange};
+use crate::services::mod_json::{load_mod_json, read_mod_json, save_mod_json, MOD_JSON_FILE_NAME};
+use crate::services::mod_service::Mod;
+use crate::services::storage::{find_library_root, replace_atomic, resolve_mod_file};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BoundKey {
+    pub mod_id: String,
+    pub mod_name: String,
+    pub mod_path: String,
+    pub binding: KeyBinding,
+}
+
+/// Bindings from different enabled mods that fire on the same key press.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeybindConflict {
+    // Key name shared by every binding, e.g. `F5`
+    pub key: String,
+    pub bindings: Vec<BoundKey>,
+}
+
+#[tauri::command]
+pub async fn find_keybind_conflicts(path: String) -> Result<Vec<KeybindConflict>, AppError> {
+    let dir = Path::new(&path);
+
+    if !dir.exists() || !dir.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let mods = load_library(dir).await?;
+    Ok(detect_keybind_conflicts(&mods))
+}
+
+pub fn detect_keybind_conflicts(mods: &[Mod]) -> Vec<KeybindConflict> {
+    let mut by_key: BTreeMap<String, Vec<BoundKey>> = BTreeMap::new();
+
+    for mod_data in mods.iter().filter(|mod_data| mod_data.enabled) {
+        for binding in &mod_data.ini.keybinds {
+            if binding.combination.key.is_empty() {
+                continue;
+            }
+
+            by_key
+                .entry(binding.combination.key.clone())
+                .or_default()
+                .push(BoundKey {
+                    mod_id: mod_data.id.clone(),
+                    mod_name: mod_data.name.clone(),
+                    mod_path: mod_data.path.clone(),
+                    binding: binding.clone(),
+                });
+        }
+    }
+
+    by_key
+        .into_iter()
+        .filter_map(|(key, bindings)| {
+            // Keep only bindings that overlap with a binding from another mod
+            let colliding = bindings
+                .iter()
+                .filter(|bound| {
+                    bindings.iter().any(|other| {
+                        other.mod_id != bound.mod_id
+                            && other
+                                .binding
+                                .combination
+                                .overlaps(&bound.binding.combination)
+                    })
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            (!colliding.is_empty()).then_some(KeybindConflict {
+                key,
+                bindings: colliding,
+            })
+        })
+        .collect()
+}
+
+/// A user rebind of one `key =` line, stored in mod.json.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyOverride {
+    // Ini file relative to the mod folder
+    pub file: String,
+    pub section: String,
+    // Which `key =` line of the section, for sections with several bindings
+    #[serde(default)]
+    pub occurrence: usize,
+    pub key: String,
+    // Value shipped by the mod author, kept so the binding can be restored
+    pub original: String,
+}
+
+#[tauri::command]
+pub fn rebind_key(
+    path: String,
+    file: String,
+    section: String,
+    occurrence: Option<usize>,
+    key: String,
+) -> Result<Vec<KeyBinding>, AppError> {
+    let mod_dir = Path::new(&path);
+
+    if !mod_dir.exists() || !mod_dir.is_dir() {
+        return Err(AppError::not_found("Mod directory does not exist"));
+    }
+
+    let key = key.trim().to_string();
+    if key.contains(['\n', '\r']) || parse_key_combination(&key).key.is_empty() {
+        return Err(AppError::invalid_input(format!(
+            "Invalid key binding '{}'",
+            key
+        )));
+    }
+
+    let occurrence = occurrence.unwrap_or(0);
+    let ini_path = resolve_mod_file(mod_dir, &file, "Ini file")?;
+    let root = find_library_root(mod_dir)?;
+    // Read before touching the ini so an unreadable mod.json stops the rebind
+    let mut mod_json = read_mod_json(mod_dir)?;
+    let mod_json_path = mod_dir.join(MOD_JSON_FILE_NAME);
+    let label = format!("Rebind [{}] to {}", section, key);
+    // Refused before anything is written, a re-encoded ini would break its other lines
+    let ini_before = read_ini_text(&ini_path)?;
+    let mod_json_before = read_text(&mod_json_path);
+
+    // Only the first rebind backs up, so the backup always holds the author's version
+    backup_ini(&ini_path, false)?;
+    let original = rewrite_key_in_file(&ini_path, &section, occurrence, &key)?;
+
+    match mod_json.key_overrides.iter_mut().find(|existing| {
+        existing.file == file
+            && existing.section.eq_ignore_ascii_case(&section)
+            && existing.occurrence == occurrence
+    }) {
+        Some(existing) => existing.key = key,
+        None => mod_json.key_overrides.push(KeyOverride {
+            file,
+            section,
+            occurrence,
+            key,
+            original,
+        }),
+    }
+    save_mod_json(mod_dir, &mod_json)?;
+
+    record(
+        &root,
+        &label,
+        vec![
+            FsChange::write(&ini_path, Some(ini_before)),
+            FsChange::write(&mod_json_path, mod_json_before),
+        ],
+    );
+
+    Ok(summarize_mod(mod_dir).keybinds)
+}
+
+/// Rewrites the stored rebinds into freshly installed ini files. Failures are logged and
+/// skipped, since an update may have renamed the section or file. `extracted` is set right
+/// after an archive was unpacked over the mod, when every ini is the author's version.
+pub fn reapply_key_overrides(mod_dir: &Path, extracted: bool) {
+    let mod_json = load_mod_json(mod_dir);
+    let mut backed_up = Vec::new();
+
+    for key_override in &mod_json.key_overrides {
+        let result =
+            resolve_mod_file(mod_dir, &key_override.file, "Ini file").and_then(|ini_path| {
+                // A file still holding the rebinds is the user's, its backup keeps the author's
+                if !backed_up.contains(&ini_path) {
+                    let fresh = extracted
+                        || !holds_overrides(&ini_path, &key_override.file, &mod_json.key_overrides);
+                    backup_ini(&ini_path, fresh)?;
+                    backed_up.push(ini_path.clone());
+                }
+                rewrite_key_in_file(
+                    &ini_path,
+                    &key_override.section,
+                    key_override.occurrence,
+                    &key_override.key,
+                )
+            });
+
+        match result {
+            Ok(_) => println!(
+                "[keybind] Reapplied [{}] key = {} in {}",
+                key_override.section, key_override.key, key_override.file
+            ),
+            Err(error) => eprintln!(
+                "[keybind] Could not reapply [{}] in {}: {}",
+                key_override.section, key_override.file, error
+            ),
+        }
+    }
+}
+
+// Whether every rebind of `file` is already in the ini. Sections that are gone don't count.
+fn holds_overrides(ini_path: &Path, file: &str, key_overrides: &[KeyOverride]) -> bool {
+    let Ok(content) = read_ini_text(ini_path) else {
+        return false;
+    };
+
+    key_overrides
+        .iter()
+        .filter(|key_override| key_override.file == file)
+        .all(|key_override| {
+            rewrite_key_line(&content, &key_override.section, key_override.occurrence, "")
+                .map_or(true, |(_, current)| current == key_override.key)
+        })
+}
+
+// `Skin.ini.bak` is not loaded by 3DMigoto, which only picks up `*.ini`
+fn backup_ini(ini_path: &Path, replace: bool) -> Result<(), AppError> {
+    let mut backup_name = ini_path.as_os_str().to_owned();
+    backup_name.push(".bak");
+    let backup_path = PathBuf::from(backup_name);
+
+    if replace || !backup_path.exists() {
+        fs::copy(ini_path, &backup_path).map_err(|e| AppError::io("Failed to back up ini", e))?;
+    }
+
+    Ok(())
+}
+
+fn rewrite_key_in_file(
+    ini_path: &Path,
+    section: &str,
+    occurrence: usize,
+    key: &str,
+) -> Result<String, AppError> {
+    let content = read_ini_text(ini_path)?;
+    let (rewritten, original) = rewrite_key_line(&content, section, occurrence, key)?;
+
+    replace_atomic(ini_path, rewritten)?;
+    Ok(original)
+}
+
+// Inis in other encodings (CP1252, GBK) are left alone rather than rewritten lossily
+fn read_ini_text(ini_path: &Path) -> Result<String, AppError> {
+    let bytes = fs::read(ini_path)
+        .map_err(|e| AppError::io("Failed to read ini", e).with_path(ini_path))?;
+    String::from_utf8(bytes).map_err(|_| {
+        AppError::invalid_data("Only UTF-8 ini files can be rebound").with_path(ini_path)
+    })
+}
+
+/// Replaces the value of the `occurrence`-th `key =` line in `[section]`, keeping
+/// indentation, trailing comments and line endings intact. Returns the new content and
+/// the previous value.
+pub fn rewrite_key_line(
+    content: &str,
+    section: &str,
+    occurrence: usize,
+    key: &str,
+) -> Result<(String, String), AppError> {
+    let mut output = String::with_capacity(content.len() + key.len());
+    let mut in_section = false;
+    let mut seen = 0;
+    let mut original = None;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') {
+            in_section = trimmed
+                .find(']')
+                .map(|end| trimmed[1..end].trim().eq_ignore_ascii_case(section))
+                .unwrap_or(false);
+        } else if in_section && original.is_none() {
+            if let Some((name, value)) = line.split_once('=') {
+                if name.trim().eq_ignore_ascii_case("key") {
+                    if seen == occurrence {
+                        let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                        let value = value.trim_end_matches(['\r', '\n']);
+                        let leading = &value[..value.len() - value.trim_start().len()];
+
+                        original = Some(value.trim().to_string());
+                        output.push_str(name);
+                        output.push('=');
+                        output.push_str(if leading.is_empty() { " " } else { leading });
+                        output.push_str(key);
+                        output.push_str(ending);
+                        continue;
+                    }
+                    seen += 1;
+                }
+            }
+        }
+
+        output.push_str(line);
+    }
+
+    match original {
+        Some(original) => Ok((output, original)),
+        None => Err(AppError::not_found(format!(
+            "No key binding found in section [{}]",
+            section
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_only_the_targeted_key_line() {
+        let content =
+            "[KeySwap]\r\nkey = VK_F5\r\ntype = cycle\r\n\r\n[KeyOther]\r\n  key =   ctrl 1\r\n";
+
+        let (rewritten, original) = rewrite_key_line(content, "keyother", 0, "alt 2").unwrap();
+
+        assert_eq!(original, "ctrl 1");
+        assert_eq!(
+            rewritten,
+            "[KeySwap]\r\nkey = VK_F5\r\ntype = cycle\r\n\r\n[KeyOther]\r\n  key =   alt 2\r\n"
+        );
+        assert!(rewrite_key_line(content, "KeyMissing", 0, "F1").is_err());
+    }
+
+    #[test]
+    fn keeps_the_author_backup_when_the_ini_still_holds_the_rebind() {
+        let mod_dir = std::env::temp_dir().join(format!("wuwa-keybind-{}", uuid::Uuid::new_v4()));
+        let ini_path = mod_dir.join("Skin.ini");
+        let backup_path = mod_dir.join("Skin.ini.bak");
+        fs::create_dir_all(&mod_dir).unwrap();
+        let mut mod_json = load_mod_json(&mod_dir);
+        mod_json.key_overrides.push(KeyOverride {
+            file: "Skin.ini".to_string(),
+            section: "KeySwap".to_string(),
+            occurrence: 0,
+            key: "F6".to_string(),
+            original: "F5".to_string(),
+        });
+        save_mod_json(&mod_dir, &mod_json).unwrap();
+        fs::write(&ini_path, "[KeySwap]\nkey = F6\n").unwrap();
+        fs::write(&backup_path, "[KeySwap]\nkey = F5\n").unwrap();
+
+        // Moved in as is, the ini is still the rebound one
+        reapply_key_overrides(&mod_dir, false);
+        let kept = fs::read_to_string(&backup_path).unwrap();
+
+        // An update from the author
+        fs::write(&ini_path, "[KeySwap]\nkey = F7\n").unwrap();
+        reapply_key_overrides(&mod_dir, false);
+        let refreshed = fs::read_to_string(&backup_path).unwrap();
+        let rebound = fs::read_to_string(&ini_path).unwrap();
+
+        // `; Café` in CP1252 is not rewritten at all
+        let cp1252 = b"[KeySwap]\n; Caf\xe9\nkey = F7\n";
+        fs::write(&ini_path, cp1252).unwrap();
+        let refused = rewrite_key_in_file(&ini_path, "KeySwap", 0, "F6").is_err();
+        let untouched = fs::read(&ini_path).unwrap() == cp1252;
+        fs::remove_dir_all(&mod_dir).unwrap();
+
+        assert_eq!(kept, "[KeySwap]\nkey = F5\n");
+        assert_eq!(refreshed, "[KeySwap]\nkey = F7\n");
+        assert_eq!(rebound, "[KeySwap]\nkey = F6\n");
+        assert!(refused && untouched);
+    }
+}
@@ -1,6 +1,28 @@
 // named the file mod.rs for rust module system, but it serves as a central place to re-export all services
+pub mod bulk;
 pub mod category;
 pub mod character;
+pub mod conflict;
+pub mod discovery;
+pub mod enable_state;
+pub mod error;
 pub mod game;
+pub mod hash_remap;
+pub mod image_protocol;
+pub mod index;
+pub mod ini;
+pub mod journal;
+pub mod keybind;
+pub mod mod_json;
 pub mod mod_service;
 pub mod preset;
+pub mod preview;
+pub mod query;
+pub mod search;
+pub mod settings;
+pub mod storage;
+pub mod texture;
+pub mod trash;
+pub mod user_vars;
+pub mod validation;
+pub mod watcher;
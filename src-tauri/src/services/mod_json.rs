@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::services::error::AppError;
+use crate::services::keybind::KeyOverride;
+use crate::services::storage::{read_recovering, write_atomic};
+
+pub const MOD_JSON_FILE_NAME: &str = "mod.json";
+/// Schema written by this version of the manager. Files without `schemaVersion` are 0.
+pub const MOD_JSON_SCHEMA_VERSION: u32 = 1;
+// Unreadable files are copied to `mod.json.corrupt-<millis>`, the same name storage uses
+// when it recovers one from `mod.json.bak`
+const CORRUPT_BACKUP_PREFIX: &str = "mod.json.corrupt-";
+
+// Entry `n` upgrades a file from schema `n` to `n + 1`
+const MIGRATIONS: [fn(&mut Map<String, Value>); 1] = [migrate_v0_to_v1];
+
+const STRING_FIELDS: [&str; 6] = ["id", "author", "description", "version", "category", "url"];
+const LIST_FIELDS: [&str; 2] = ["keyOverrides", "gallery"];
+const MAX_RATING: u8 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModJson {
+    pub schema_version: u32,
+    pub id: String,
+    pub author: String,
+    pub description: String,
+    pub version: String,
+    pub category: String,
+    pub url: String,
+    // User key rebinds, reapplied whenever the mod is reinstalled
+    pub key_overrides: Vec<KeyOverride>,
+    // Preview images found on install besides the one used for thumbnail.png
+    pub gallery: Vec<String>,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    // 1 to 5 stars, None when unrated
+    pub rating: Option<u8>,
+    pub notes: String,
+    // Unix millis
+    pub installed_at: Option<u64>,
+    pub last_enabled_at: Option<u64>,
+    // Fields from newer versions or other tools, written back untouched
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for ModJson {
+    fn default() -> Self {
+        ModJson {
+            schema_version: MOD_JSON_SCHEMA_VERSION,
+            id: String::new(),
+            author: String::new(),
+            description: String::new(),
+            version: String::new(),
+            category: String::new(),
+            url: String::new(),
+            key_overrides: Vec::new(),
+            gallery: Vec::new(),
+            tags: Vec::new(),
+            favorite: false,
+            rating: None,
+            notes: String::new(),
+            installed_at: None,
+            last_enabled_at: None,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// The mod's `mod.json`, upgraded to the current schema, or defaults when it can't be read.
+/// Only for reading: code that saves the file goes through [`read_mod_json`] so defaults never
+/// replace a damaged file.
+pub fn load_mod_json(mod_dir: &Path) -> ModJson {
+    read_mod_json(mod_dir).unwrap_or_else(|error| {
+        eprintln!("[mod.json] {}: {}", mod_dir.display(), error);
+        ModJson::default()
+    })
+}
+
+/// Like [`load_mod_json`] but reports an unreadable file instead of falling back to defaults.
+/// A damaged file is replaced by its last good copy when there is one, otherwise it is left
+/// in place and copied aside (see [`corrupt_backups`]). Files from an older schema are
+/// migrated and saved.
+pub fn read_mod_json(mod_dir: &Path) -> Result<ModJson, AppError> {
+    let path = mod_dir.join(MOD_JSON_FILE_NAME);
+    let Some(contents) = read_recovering(&path, |contents| {
+        matches!(serde_json::from_str(contents), Ok(Value::Object(_)))
+    }) else {
+        return Ok(ModJson::default());
+    };
+
+    let mut value = match serde_json::from_str::<Value>(&contents) {
+        Ok(Value::Object(value)) => value,
+        Ok(_) => return Err(back_up_corrupt(&path, "not a JSON object")),
+        Err(error) => return Err(back_up_corrupt(&path, &error.to_string())),
+    };
+
+    let from_version = migrate(&mut value);
+    let mod_json = serde_json::from_value::<ModJson>(Value::Object(value))
+        .map_err(|error| back_up_corrupt(&path, &error.to_string()))?;
+
+    if from_version < MOD_JSON_SCHEMA_VERSION {
+        println!(
+            "[mod.json] Migrated {} from schema {} to {}",
+            path.display(),
+            from_version,
+            MOD_JSON_SCHEMA_VERSION
+        );
+        save_mod_json(mod_dir, &mod_json)?;
+    } else if from_version > MOD_JSON_SCHEMA_VERSION {
+        eprintln!(
+            "[mod.json] {} uses schema {}, newer than {}; unknown fields are kept",
+            path.display(),
+            from_version,
+            MOD_JSON_SCHEMA_VERSION
+        );
+    }
+
+    Ok(mod_json)
+}
+
+pub fn save_mod_json(mod_dir: &Path, mod_json: &ModJson) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(mod_json)?;
+    write_atomic(&mod_dir.join(MOD_JSON_FILE_NAME), json)
+}
+
+/// Stamps the mod as enabled just now. Failures are only logged, the toggle itself worked.
+pub fn record_enabled(mod_dir: &Path) {
+    let mut mod_json = match read_mod_json(mod_dir) {
+        Ok(mod_json) => mod_json,
+        Err(error) => {
+            eprintln!("[mod.json] Not recording enable time: {}", error);
+            return;
+        }
+    };
+    mod_json.last_enabled_at = Some(now_millis());
+    if let Err(error) = save_mod_json(mod_dir, &mod_json) {
+        eprintln!("[mod.json] Failed to record enable time: {}", error);
+    }
+}
+
+/// Trimmed tags without empties or case-insensitive duplicates, first spelling wins.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()) {
+        if !tag.is_empty()
+            && !normalized
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(tag))
+        {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+pub fn validate_rating(rating: Option<u8>) -> Result<Option<u8>, AppError> {
+    match rating {
+        Some(stars) if !(1..=MAX_RATING).contains(&stars) => Err(AppError::invalid_input(format!(
+            "Rating must be between 1 and {}, got {}",
+            MAX_RATING, stars
+        ))),
+        _ => Ok(rating),
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Backups of unreadable mod.json files left in the mod folder, oldest first.
+pub fn corrupt_backups(mod_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(mod_dir) else {
+        return Vec::new();
+    };
+
+    let mut backups = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| is_corrupt_backup(name))
+        .collect::<Vec<_>>();
+    backups.sort();
+    backups
+}
+
+pub fn is_corrupt_backup(file_name: &str) -> bool {
+    file_name.starts_with(CORRUPT_BACKUP_PREFIX)
+}
+
+// Runs every migration the file still needs and returns the schema it started at
+fn migrate(value: &mut Map<String, Value>) -> u32 {
+    let from_version = value
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .map(|version| version as u32)
+        .unwrap_or(0);
+
+    for migration in MIGRATIONS.iter().skip(from_version as usize) {
+        migration(value);
+    }
+
+    // Never downgrade a file written by a newer manager
+    let version = from_version.max(MOD_JSON_SCHEMA_VERSION);
+    value.insert("schemaVersion".to_string(), Value::from(version));
+    from_version
+}
+
+// Unversioned files were often edited by hand or written by other tools, with numbers
+// where strings are expected and nulls for empty fields
+fn migrate_v0_to_v1(value: &mut Map<String, Value>) {
+    for field in STRING_FIELDS {
+        let migrated = match value.get(field) {
+            Some(Value::Number(number)) => number.to_string(),
+            Some(Value::Bool(flag)) => flag.to_string(),
+            Some(Value::Null) => String::new(),
+            _ => continue,
+        };
+        value.insert(field.to_string(), Value::String(migrated));
+    }
+
+    for field in LIST_FIELDS {
+        if value.get(field).is_some_and(Value::is_null) {
+            value.remove(field);
+        }
+    }
+}
+
+fn back_up_corrupt(path: &Path, error: &str) -> AppError {
+    let contents = fs::read(path).unwrap_or_default();
+    let mod_dir = path.parent().unwrap_or(path);
+    // Every read of the file lands here until it is fixed, one copy is enough
+    let backed_up = corrupt_backups(mod_dir)
+        .into_iter()
+        .any(|backup| fs::read(mod_dir.join(backup)).is_ok_and(|backup| backup == contents));
+    let backup = path.with_file_name(format!("{}{}", CORRUPT_BACKUP_PREFIX, now_millis()));
+
+    let message = if backed_up {
+        format!("mod.json could not be read ({})", error)
+    } else {
+        match fs::copy(path, &backup) {
+            Ok(_) => format!(
+                "mod.json could not be read ({}), a copy was saved as {}",
+                error,
+                backup.display()
+            ),
+            Err(copy_error) => format!(
+                "mod.json could not be read ({}) nor backed up: {}",
+                error, copy_error
+            ),
+        }
+    };
+    AppError::invalid_data(message).with_path(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_mod_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wuwa-modjson-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn migrates_unversioned_files_and_keeps_unknown_fields() {
+        let dir = temp_mod_dir();
+        fs::write(
+            dir.join(MOD_JSON_FILE_NAME),
+            r#"{"id":"abc","author":null,"version":2.1,"gallery":null,"source":{"site":"gb"}}"#,
+        )
+        .unwrap();
+
+        let mod_json = read_mod_json(&dir).unwrap();
+        let saved: Value =
+            serde_json::from_str(&fs::read_to_string(dir.join(MOD_JSON_FILE_NAME)).unwrap())
+                .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(mod_json.schema_version, MOD_JSON_SCHEMA_VERSION);
+        assert_eq!(mod_json.id, "abc");
+        assert_eq!(mod_json.version, "2.1");
+        assert_eq!(saved["schemaVersion"], MOD_JSON_SCHEMA_VERSION);
+        assert_eq!(saved["source"]["site"], "gb");
+    }
+
+    #[test]
+    fn normalizes_tags_and_rejects_out_of_range_ratings() {
+        let tags = ["  Summer ", "summer", "", "NSFW"].map(String::from);
+
+        assert_eq!(normalize_tags(&tags), vec!["Summer", "NSFW"]);
+        assert_eq!(validate_rating(Some(5)), Ok(Some(5)));
+        assert_eq!(validate_rating(None), Ok(None));
+        assert!(validate_rating(Some(0)).is_err());
+        assert!(validate_rating(Some(6)).is_err());
+    }
+
+    #[test]
+    fn recovers_truncated_files_from_the_last_good_copy() {
+        let dir = temp_mod_dir();
+        let mut mod_json = ModJson {
+            author: "Mia".to_string(),
+            ..ModJson::default()
+        };
+        save_mod_json(&dir, &mod_json).unwrap();
+        mod_json.notes = "Hand-tuned".to_string();
+        save_mod_json(&dir, &mod_json).unwrap();
+        fs::write(dir.join(MOD_JSON_FILE_NAME), "{\"author\": \"Mi").unwrap();
+
+        let recovered = read_mod_json(&dir).unwrap();
+        let backups = corrupt_backups(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(recovered.author, "Mia");
+        assert_eq!(recovered.notes, "Hand-tuned");
+        assert_eq!(backups.len(), 1);
+    }
+
+    #[test]
+    fn backs_up_unreadable_files_instead_of_resetting_them() {
+        let dir = temp_mod_dir();
+        fs::write(dir.join(MOD_JSON_FILE_NAME), "{\"id\": \"abc\",").unwrap();
+
+        let result = read_mod_json(&dir);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let read_again = read_mod_json(&dir);
+        record_enabled(&dir);
+        let backups = corrupt_backups(&dir);
+        let backup = fs::read_to_string(dir.join(&backups[0])).unwrap();
+        let mod_json = fs::read_to_string(dir.join(MOD_JSON_FILE_NAME)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_err());
+        assert!(read_again.is_err());
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backup, "{\"id\": \"abc\",");
+        assert_eq!(mod_json, "{\"id\": \"abc\",");
+    }
+}
@@ -3,10 +3,13 @@ use std::fs::{self, File};
 use std::io::{self, Read};
 use std::process::Command;
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock};
 
 use base64::{engine::general_purpose, Engine as _};
 use futures_util::StreamExt;
-use tauri::Emitter;
+use image::RgbaImage;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use regex::Regex;
 use reqwest::Client;
 use uuid::Uuid;
@@ -16,8 +19,32 @@ use tokio::io::AsyncWriteExt;
 
 use crate::services::category::{find_matching_category, load_character_categories, Category};
 use crate::services::character::scrape_characters;
-
-#[derive(Debug, Serialize, Deserialize)]
+use crate::services::conflict::{conflicts_involving, HashConflict};
+use crate::services::discovery::{ensure_group_enabled, in_disabled_group, library_root};
+use crate::services::enable_state::{name_for_state, parse_name};
+use crate::services::error::{AppError, ErrorCode};
+use crate::services::index::{library_loaded, load_library};
+use crate::services::ini::{list_ini_files, summarize_mod, IniFileState, IniSummary};
+use crate::services::journal::{created, read_text, record, FsChange};
+use crate::services::keybind::reapply_key_overrides;
+use crate::services::mod_json::{
+    normalize_tags, now_millis, read_mod_json, save_mod_json, validate_rating, ModJson,
+    MOD_JSON_FILE_NAME,
+};
+use crate::services::preview::{import_previews, replace_thumbnail};
+use crate::services::settings::load_settings;
+use crate::services::storage::find_library_root;
+use crate::services::texture::{decode_texture, fallback_thumbnail, grid_thumbnail};
+use crate::services::trash::trash_mod;
+use crate::services::user_vars::{read_user_vars, vars_for_mod, PersistedVar};
+
+// Extracts a version from folder names such as "mod_v1.0"
+static VERSION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)v?\.?(\d+\.\d+)").unwrap());
+
+static SCRAPED_CATEGORIES: OnceLock<Vec<Category>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Mod {
     pub id: String,
@@ -32,18 +59,45 @@ pub struct Mod {
     // not json stored
     pub name: String,
     pub path: String,
+    #[serde(default)]
+    pub group: String,
     pub thumbnail: String,
+    // Small cached copy of the thumbnail for the library grid
+    #[serde(default)]
+    pub grid_thumbnail: String,
     pub enabled: bool,
+    #[serde(default)]
+    pub ini: IniSummary,
+    #[serde(default)]
+    pub ini_files: Vec<IniFileState>,
+    // Values saved by 3DMigoto in d3dx_user.ini, not cached in the index
+    #[serde(default)]
+    pub user_vars: Vec<PersistedVar>,
+    // Extra preview images, relative to the mod folder
+    #[serde(default)]
+    pub gallery: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub rating: Option<u8>,
+    #[serde(default)]
+    pub notes: String,
+    // Unix millis, set by the manager rather than the UI
+    #[serde(default)]
+    pub installed_at: Option<u64>,
+    #[serde(default)]
+    pub last_enabled_at: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct ModJson {
-    pub id: String,
-    pub author: String,
-    pub description: String,
-    pub version: String,
-    pub category: String,
-    pub url: String,
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModInfoResult {
+    #[serde(rename = "mod")]
+    pub mod_data: Mod,
+    // Hash conflicts caused by enabling this mod
+    pub conflicts: Vec<HashConflict>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -76,7 +130,7 @@ fn sanitize_dir_name(name: &str) -> String {
     }
 }
 
-fn create_unique_mod_dir(root: &Path, suggested_name: &str) -> Result<PathBuf, String> {
+fn create_unique_mod_dir(root: &Path, suggested_name: &str) -> Result<PathBuf, AppError> {
     let base_name = sanitize_dir_name(suggested_name);
     let mut candidate = root.join(&base_name);
     let mut suffix = 1;
@@ -86,14 +140,17 @@ fn create_unique_mod_dir(root: &Path, suggested_name: &str) -> Result<PathBuf, S
         suffix += 1;
     }
 
-    fs::create_dir_all(&candidate).map_err(|e| format!("Failed to create mod directory: {e}"))?;
+    fs::create_dir_all(&candidate)
+        .map_err(|e| AppError::io("Failed to create mod directory", e))?;
     Ok(candidate)
 }
 
-fn detect_archive_format(path: &Path) -> Result<Option<ArchiveFormat>, String> {
-    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {e}"))?;
+fn detect_archive_format(path: &Path) -> Result<Option<ArchiveFormat>, AppError> {
+    let mut file = File::open(path).map_err(|e| AppError::io("Failed to open file", e))?;
     let mut magic = [0u8; 8];
-    let n = file.read(&mut magic).map_err(|e| format!("Failed to inspect file: {e}"))?;
+    let n = file
+        .read(&mut magic)
+        .map_err(|e| AppError::io("Failed to inspect file", e))?;
 
     if n >= 4 && magic[0..4] == [0x50, 0x4B, 0x03, 0x04] {
         Ok(Some(ArchiveFormat::Zip))
@@ -108,14 +165,8 @@ fn detect_archive_format(path: &Path) -> Result<Option<ArchiveFormat>, String> {
     }
 }
 
-fn write_mod_metadata(mod_dir: &Path, url: Option<&str>) -> Result<(), String> {
-    let mod_json_path = mod_dir.join("mod.json");
-    let mut mod_json: ModJson = if mod_json_path.exists() {
-        let content = fs::read_to_string(&mod_json_path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        ModJson::default()
-    };
+fn write_mod_metadata(mod_dir: &Path, url: Option<&str>) -> Result<(), AppError> {
+    let mut mod_json = read_mod_json(mod_dir)?;
 
     if let Some(url) = url {
         mod_json.url = url.to_string();
@@ -123,37 +174,47 @@ fn write_mod_metadata(mod_dir: &Path, url: Option<&str>) -> Result<(), String> {
     if mod_json.id.is_empty() {
         mod_json.id = Uuid::new_v4().to_string();
     }
+    // Reinstalls keep the original install date
+    if mod_json.installed_at.is_none() {
+        mod_json.installed_at = Some(now_millis());
+    }
 
-    let json = serde_json::to_string_pretty(&mod_json).map_err(|e| e.to_string())?;
-    fs::write(&mod_json_path, json).map_err(|e| e.to_string())?;
-
-    Ok(())
+    save_mod_json(mod_dir, &mod_json)
 }
 
-fn extract_zip_archive(archive_path: &Path, mod_dir: &Path) -> Result<(), String> {
-    let cursor = File::open(archive_path)
-        .map_err(|e| format!("Failed to open archive file: {e}"))?;
+fn extract_zip_archive(archive_path: &Path, mod_dir: &Path) -> Result<(), AppError> {
+    let cursor =
+        File::open(archive_path).map_err(|e| AppError::io("Failed to open archive file", e))?;
 
-    let mut archive = ZipArchive::new(cursor).map_err(|e| format!("Failed to open ZIP: {e}"))?;
+    let mut archive = ZipArchive::new(cursor)
+        .map_err(|e| AppError::invalid_data(format!("Failed to open ZIP: {e}")))?;
 
     for i in 0..archive.len() {
-        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| AppError::invalid_data(e.to_string()))?;
 
         let Some(relative_path) = file.enclosed_name().map(|path| path.to_owned()) else {
-            return Err("ZIP archive contains an unsafe path".to_string());
+            return Err(AppError::invalid_input(
+                "ZIP archive contains an unsafe path",
+            ));
         };
 
         let outpath = mod_dir.join(relative_path);
 
-        if file.name().ends_with('/') {
-            fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+        let extracted = if file.name().ends_with('/') {
+            fs::create_dir_all(&outpath)
         } else {
-            if let Some(parent) = outpath.parent() {
-                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-            }
-            let mut outfile = File::create(&outpath).map_err(|e| e.to_string())?;
-            std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
-        }
+            outpath
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| File::create(&outpath))
+                .and_then(|mut outfile| std::io::copy(&mut file, &mut outfile))
+                .map(|_| ())
+        };
+        extracted.map_err(|e| {
+            AppError::io(format!("Failed to extract {}", outpath.display()), e).with_path(&outpath)
+        })?;
     }
 
     Ok(())
@@ -163,7 +224,7 @@ fn extract_archive_to_dir(
     archive_path: &Path,
     mod_dir: &Path,
     url: Option<&str>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     match detect_archive_format(archive_path)? {
         Some(ArchiveFormat::Zip) => extract_zip_archive(archive_path, mod_dir)?,
         Some(ArchiveFormat::SevenZip) | Some(ArchiveFormat::Rar) => {
@@ -173,248 +234,326 @@ fn extract_archive_to_dir(
                 .arg(format!("-o{}", mod_dir.display()))
                 .arg(archive_path.to_string_lossy().to_string())
                 .output()
-                .map_err(|e| format!("Failed to execute 7z: {e}"))?;
+                .map_err(|e| match e.kind() {
+                    io::ErrorKind::NotFound => AppError::new(
+                        ErrorCode::ToolMissing,
+                        "7z is required to extract .7z and .rar mods but was not found",
+                    ),
+                    _ => AppError::io("Failed to execute 7z", e),
+                })?;
 
             if !output.status.success() {
-                return Err(format!(
+                return Err(AppError::invalid_data(format!(
                     "7z extraction failed: {}",
                     String::from_utf8_lossy(&output.stderr)
-                ));
+                ))
+                .with_path(archive_path));
             }
         }
-        None => return Err("Unsupported compressed mod format".to_string()),
+        None => return Err(AppError::invalid_input("Unsupported compressed mod format")),
     }
 
     write_mod_metadata(mod_dir, url)?;
+    reapply_key_overrides(mod_dir, true);
+    if let Err(error) = import_previews(mod_dir) {
+        eprintln!("[preview] {}", error);
+    }
     let _ = fs::remove_file(archive_path);
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_folder_mods(path: String) -> Result<Vec<Mod>, String> {
+pub async fn get_folder_mods(path: String, app: AppHandle) -> Result<Vec<Mod>, AppError> {
     let dir = Path::new(&path);
 
     if !dir.exists() || !dir.is_dir() {
-        return Err("Invalid directory path".to_string());
+        return Err(AppError::not_found("Invalid directory path"));
     }
 
-    // Prefer the live character list; fall back to the bundled list if scraping fails.
-    let categories = load_auto_match_categories().await;
-
-    // Regex to extract version from folder name (e.g., "mod_v1.0")
-    let version_regex = Regex::new(r"(?i)v?\.?(\d+\.\d+)").unwrap();
+    let mut mods = load_library(dir).await?;
 
-    let mut mods = Vec::new();
-
-    for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-
-        if !path.is_dir() {
-            continue;
+    // d3dx_user.ini changes while the game runs, so it is read fresh on every listing
+    match read_user_vars(dir) {
+        Ok(vars) if !vars.is_empty() => {
+            for mod_data in mods.iter_mut() {
+                mod_data.user_vars = vars_for_mod(dir, mod_data, &vars);
+            }
         }
+        Ok(_) => {}
+        Err(error) => eprintln!("[mods] {}", error),
+    }
 
-        let name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        let (enabled, display_name) = if name.starts_with("disabled ") {
-            (false, name["disabled ".len()..].to_string())
-        } else {
-            (true, name.clone())
-        };
+    library_loaded(&app, dir, &mods);
+    Ok(mods)
+}
 
-        let thumbnail_path = path.join("thumbnail.png");
-        let thumbnail = if thumbnail_path.exists() {
-            thumbnail_path.to_string_lossy().to_string()
-        } else {
-            String::new()
-        };
+/// Builds the `Mod` for a single mod folder, writing a fresh id into `mod.json` when missing.
+pub fn read_mod(path: &Path, group: String, categories: &[Category]) -> Mod {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
 
-        let details_path = path.join("mod.json");
-        let mut details = if details_path.exists() {
-            match std::fs::read_to_string(&details_path) {
-                Ok(contents) => serde_json::from_str::<ModJson>(&contents).unwrap_or_default(),
-                Err(_) => ModJson::default(),
-            }
-        } else {
-            ModJson::default()
-        };
+    let (enabled, display_name) = parse_name(&name);
+    let enabled = enabled && !in_disabled_group(&group);
+    let display_name = display_name.to_string();
 
-        // Generate a new ID if one doesn't exist
-        if details.id.is_empty() {
-            details.id = Uuid::new_v4().to_string();
-            // Save the updated mod.json with the new ID
-            if let Ok(json) = serde_json::to_string_pretty(&details) {
-                if let Err(e) = fs::write(&details_path, json) {
-                    eprintln!("Failed to write mod.json: {}", e);
-                }
-            }
+    // An unreadable mod.json is listed with defaults but never saved over
+    let (mut details, readable) = match read_mod_json(path) {
+        Ok(details) => (details, true),
+        Err(error) => {
+            eprintln!("[mod.json] {}: {}", path.display(), error);
+            (ModJson::default(), false)
         }
+    };
+    let missing_id = details.id.is_empty();
+    let missing_install_date = details.installed_at.is_none();
 
-        // Extract version from folder name if not available in mod.json
-        let version = if !details.version.is_empty() {
-            details.version
-        } else {
-            version_regex
-                .captures(&name)
-                .and_then(|cap| cap.get(1))
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_else(|| "".to_string())
-        };
+    // Generate a new ID if one doesn't exist
+    if missing_id {
+        details.id = Uuid::new_v4().to_string();
+    }
+    // Mods installed by hand or before install dates were recorded use the folder's age
+    if missing_install_date {
+        details.installed_at = Some(folder_created_millis(path));
+    }
+    if readable && (missing_id || missing_install_date) {
+        // Save the updated mod.json with the new ID and install date
+        if let Err(e) = save_mod_json(path, &details) {
+            eprintln!("Failed to write mod.json: {}", e);
+        }
+    }
 
-        let name = display_name.clone();
+    // Extract version from folder name if not available in mod.json
+    let version = if !details.version.is_empty() {
+        details.version
+    } else {
+        VERSION_REGEX
+            .captures(&name)
+            .and_then(|cap| cap.get(1))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default()
+    };
 
-        // If category is not set in mod.json, try to determine it from the mod name
-        if details.category.is_empty() {
-            if let Some(category) = find_matching_category(&name, &categories) {
-                details.category = category;
-            }
+    // If category is not set in mod.json, try to determine it from the mod name
+    if details.category.is_empty() {
+        if let Some(category) = find_matching_category(&display_name, categories) {
+            details.category = category;
         }
+    }
 
-        // Get category and url from mod.json, fallback to empty string if not present
-        let author = details.author;
-        let description = details.description;
-        let category = details.category;
-        let url = details.url;
-
-        mods.push(Mod {
-            id: details.id,
-            name,
-            path: path.to_string_lossy().to_string(),
-            author,
-            description,
-            version,
-            category,
-            url,
-            thumbnail,
-            enabled,
-        });
+    let ini = summarize_mod(path);
+
+    // Mods without a thumbnail.png get a picture of their diffuse texture
+    let root = library_root(path, &group);
+    let thumbnail_path = path.join("thumbnail.png");
+    let thumbnail = if thumbnail_path.exists() {
+        Some(thumbnail_path)
+    } else {
+        fallback_thumbnail(&root, &details.id, path, &ini)
+    };
+    let grid_thumbnail = thumbnail
+        .as_ref()
+        .and_then(|thumbnail| grid_thumbnail(&root, &details.id, thumbnail));
+
+    Mod {
+        id: details.id,
+        name: display_name,
+        path: path.to_string_lossy().to_string(),
+        group,
+        author: details.author,
+        description: details.description,
+        version,
+        category: details.category,
+        url: details.url,
+        thumbnail: path_string(thumbnail),
+        grid_thumbnail: path_string(grid_thumbnail),
+        enabled,
+        ini,
+        ini_files: list_ini_files(path),
+        user_vars: Vec::new(),
+        gallery: details.gallery,
+        tags: details.tags,
+        favorite: details.favorite,
+        rating: details.rating,
+        notes: details.notes,
+        installed_at: details.installed_at,
+        last_enabled_at: details.last_enabled_at,
     }
+}
 
-    Ok(mods)
+fn folder_created_millis(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.created().or_else(|_| metadata.modified()))
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_else(now_millis)
+}
+
+fn path_string(path: Option<PathBuf>) -> String {
+    path.map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default()
 }
 
+/// Replaces the mod thumbnail with an image file (any supported format, `.dds` included)
+/// or base64 image data, re-encoded as a standard-size PNG.
 #[tauri::command]
-pub fn set_mod_thumbnail(path: String, thumbnail_path: String, base64: String) -> Result<(), String> {
+pub fn set_mod_thumbnail(
+    path: String,
+    thumbnail_path: String,
+    base64: String,
+) -> Result<(), AppError> {
     let mod_dir = Path::new(&path);
     let src_path = Path::new(&thumbnail_path);
 
     if !mod_dir.exists() || !mod_dir.is_dir() {
-        return Err("Mod directory does not exist".to_string());
+        return Err(AppError::not_found("Mod directory does not exist"));
     }
 
-    let dest_path = mod_dir.join("thumbnail.png");
-
-    if !base64.is_empty() {
+    let image = if !base64.is_empty() {
         let decoded = general_purpose::STANDARD
             .decode(&base64)
-            .map_err(|e| e.to_string())?;
-        fs::write(&dest_path, decoded).map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::invalid_input(e.to_string()))?;
+        image::load_from_memory(&decoded)
+            .map_err(|e| AppError::invalid_input(format!("Unsupported thumbnail image: {e}")))?
+            .to_rgba8()
     } else if !thumbnail_path.is_empty() {
-        if src_path.exists() && src_path.is_file() {
-            fs::copy(&src_path, &dest_path).map_err(|e| e.to_string())?;
-        } else {
-            return Err("Thumbnail path does not exist".to_string() + src_path.to_str().unwrap());
+        if !src_path.is_file() {
+            return Err(AppError::not_found(format!(
+                "Thumbnail path {} does not exist",
+                thumbnail_path
+            ))
+            .with_path(src_path));
         }
+        decode_texture(src_path)?
     } else {
-        return Err("No thumbnail provided".to_string());
+        return Err(AppError::invalid_input("No thumbnail provided"));
+    };
+
+    replace_thumbnail(mod_dir, image)
+}
+
+#[tauri::command]
+pub fn set_mod_thumbnail_from_clipboard(app: AppHandle, path: String) -> Result<(), AppError> {
+    let mod_dir = Path::new(&path);
+
+    if !mod_dir.exists() || !mod_dir.is_dir() {
+        return Err(AppError::not_found("Mod directory does not exist"));
     }
 
-    Ok(())
+    let clipboard_image = app
+        .clipboard()
+        .read_image()
+        .map_err(|e| AppError::invalid_input(format!("Clipboard has no image: {e}")))?;
+    let image = RgbaImage::from_raw(
+        clipboard_image.width(),
+        clipboard_image.height(),
+        clipboard_image.rgba().to_vec(),
+    )
+    .ok_or_else(|| AppError::invalid_data("Clipboard image has invalid dimensions"))?;
+
+    replace_thumbnail(mod_dir, image)
 }
 
 #[tauri::command]
-pub fn read_image_file(path: String) -> Result<String, String> {
-    let bytes = fs::read(&path).map_err(|e| format!("Failed to read image file: {e}"))?;
+pub fn read_image_file(path: String) -> Result<String, AppError> {
+    let bytes = fs::read(&path).map_err(|e| AppError::io("Failed to read image file", e))?;
     Ok(general_purpose::STANDARD.encode(bytes))
 }
 
 #[tauri::command]
-pub fn set_mod_info(mod_data: Mod) -> Result<Mod, String> {
+pub async fn set_mod_info(mod_data: Mod) -> Result<ModInfoResult, AppError> {
     let mod_dir = Path::new(&mod_data.path);
 
     if !mod_dir.exists() || !mod_dir.is_dir() {
-        return Err("Mod directory does not exist".to_string());
+        return Err(AppError::not_found("Mod directory does not exist"));
     }
 
-    let details = ModJson {
-        id: mod_data.id.clone(),
-        author: mod_data.author.clone(),
-        version: mod_data.version.clone(),
-        description: mod_data.description.clone(),
-        category: mod_data.category.clone(),
-        url: mod_data.url.clone(),
-    };
-
-    let details_path = mod_dir.join("mod.json");
-    let json = serde_json::to_string_pretty(&details).map_err(|e| e.to_string())?;
-    std::fs::write(details_path, json).map_err(|e| e.to_string())?;
-
     let current_name = match mod_dir.file_name().and_then(|n| n.to_str()) {
         Some(name) => name,
-        None => return Err("Invalid mod directory name".to_string()),
+        None => return Err(AppError::invalid_input("Invalid mod directory name")),
     };
 
-    let mut new_name = mod_data.name.clone();
-    if !mod_data.enabled {
-        if !new_name.starts_with("disabled ") {
-            new_name = format!("disabled {}", new_name);
-        }
+    let (was_enabled, current_display_name) = parse_name(current_name);
+    let root = library_root(mod_dir, &mod_data.group);
+    if mod_data.enabled {
+        ensure_group_enabled(&root, mod_dir)?;
+    }
+    let label = match (was_enabled, mod_data.enabled) {
+        (false, true) => format!("Enable {}", mod_data.name),
+        (true, false) => format!("Disable {}", mod_data.name),
+        _ => format!("Edit {}", mod_data.name),
+    };
+
+    // Start from the stored file so fields the UI doesn't edit are kept
+    let mut details = read_mod_json(mod_dir)?;
+    let mod_json_path = mod_dir.join(MOD_JSON_FILE_NAME);
+    let before = read_text(&mod_json_path);
+    details.id = mod_data.id.clone();
+    details.author = mod_data.author.clone();
+    details.version = mod_data.version.clone();
+    details.description = mod_data.description.clone();
+    details.category = mod_data.category.clone();
+    details.url = mod_data.url.clone();
+    details.tags = normalize_tags(&mod_data.tags);
+    details.favorite = mod_data.favorite;
+    details.rating = validate_rating(mod_data.rating)?;
+    details.notes = mod_data.notes.clone();
+    if mod_data.enabled && !was_enabled {
+        details.last_enabled_at = Some(now_millis());
     }
 
+    save_mod_json(mod_dir, &details)?;
+    let mut changes = vec![FsChange::write(&mod_json_path, before)];
+
+    // A folder already disabled with another prefix variant keeps it until it is toggled
+    let new_name = if was_enabled == mod_data.enabled && current_display_name == mod_data.name {
+        current_name.to_string()
+    } else {
+        let prefix = load_settings(&root).disabled_prefix;
+        name_for_state(&mod_data.name, mod_data.enabled, prefix)
+    };
+
     let mut new_mod_dir = mod_dir.to_path_buf();
 
     if current_name != new_name {
         if let Some(parent) = mod_dir.parent() {
             let new_path = parent.join(&new_name);
-            std::fs::rename(mod_dir, &new_path).map_err(|e| e.to_string())?;
+            if let Err(e) = std::fs::rename(mod_dir, &new_path) {
+                // The mod.json edit still went through
+                record(&root, &label, changes);
+                return Err(AppError::io("Failed to rename mod", e).with_path(mod_dir));
+            }
+            changes.push(FsChange::Move {
+                from: mod_dir.to_path_buf(),
+                to: new_path.clone(),
+            });
             new_mod_dir = new_path;
         }
     }
 
-    let thumbnail_path = new_mod_dir.join("thumbnail.png");
-    let thumbnail = if thumbnail_path.exists() {
-        thumbnail_path.to_string_lossy().to_string()
-    } else {
-        String::new()
-    };
+    record(&root, &label, changes);
 
-    let mod_json_path = new_mod_dir.join("mod.json");
-    let details = if mod_json_path.exists() {
-        match std::fs::read_to_string(&mod_json_path) {
-            Ok(contents) => serde_json::from_str::<ModJson>(&contents).unwrap_or_default(),
-            Err(_) => ModJson::default(),
-        }
+    let updated = read_mod(&new_mod_dir, mod_data.group, &[]);
+
+    // Only warn when this call is what turned the mod on
+    let conflicts = if updated.enabled && !was_enabled {
+        conflicts_involving(&root, std::slice::from_ref(&updated.id)).await
     } else {
-        ModJson::default()
+        Vec::new()
     };
 
-    let id = new_mod_dir
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("")
-        .to_string();
-
-    Ok(Mod {
-        id,
-        name: mod_data.name,
-        path: new_mod_dir.to_string_lossy().to_string(),
-        author: details.author,
-        description: details.description,
-        version: details.version,
-        category: details.category,
-        url: details.url,
-        thumbnail,
-        enabled: mod_data.enabled,
+    Ok(ModInfoResult {
+        mod_data: updated,
+        conflicts,
     })
 }
 
 #[tauri::command]
-pub async fn download_mod(url: String, to: String, window: tauri::Window) -> Result<(), String> {
+pub async fn download_mod(url: String, to: String, window: tauri::Window) -> Result<(), AppError> {
     const TIMEOUT_SECS: u64 = 120;
     const MAX_SIZE: usize = 1024 * 1024 * 1024; // 1 GB
 
@@ -425,23 +564,27 @@ pub async fn download_mod(url: String, to: String, window: tauri::Window) -> Res
         .no_deflate()
         .no_zstd()
         .build()
-        .map_err(|e| format!("HTTP client error: {e}"))?;
+        .map_err(|e| AppError::network("HTTP client error", e))?;
 
     let response = client
         .get(&url)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {e}"))?;
+        .map_err(|e| AppError::network("Request failed", e))?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "HTTP {}: {}\nBody: {}",
-            status,
-            status.canonical_reason().unwrap_or(""),
-            body
-        ));
+        return Err(AppError::new(
+            ErrorCode::Network,
+            format!(
+                "HTTP {}: {}\nBody: {}",
+                status,
+                status.canonical_reason().unwrap_or(""),
+                body
+            ),
+        )
+        .with_status(status.as_u16()));
     }
 
     let filename = response
@@ -477,46 +620,48 @@ pub async fn download_mod(url: String, to: String, window: tauri::Window) -> Res
 
     let total_size = response.content_length().unwrap_or(0);
     if total_size as usize > MAX_SIZE {
-        return Err(format!(
+        return Err(AppError::invalid_input(format!(
             "File too large: {} > {} bytes",
             total_size, MAX_SIZE
-        ));
+        )));
     }
 
     let mod_dir = PathBuf::from(&to).join(&mod_name);
-    fs::create_dir_all(&mod_dir).map_err(|e| format!("Failed to create directory: {e}"))?;
+    let reinstall = mod_dir.exists();
+    fs::create_dir_all(&mod_dir).map_err(|e| AppError::io("Failed to create directory", e))?;
 
     let temp_path = mod_dir.join(format!(".{mod_name}.download"));
     let mut output = TokioFile::create(&temp_path)
         .await
-        .map_err(|e| format!("Failed to create download file: {e}"))?;
+        .map_err(|e| AppError::io("Failed to create download file", e))?;
 
     let mut downloaded: u64 = 0;
     let mut stream = response.bytes_stream();
 
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Error downloading: {e}"))?;
+        let chunk = chunk.map_err(|e| AppError::network("Error downloading", e))?;
         downloaded += chunk.len() as u64;
         output
             .write_all(&chunk)
             .await
-            .map_err(|e| format!("Failed to write download chunk: {e}"))?;
-
-        window
-            .emit(
-                "download-progress",
-                DownloadProgress {
-                    downloaded,
-                    total: total_size,
-                },
-            )
-            .map_err(|e| format!("Failed to emit progress: {e}"))?;
+            .map_err(|e| AppError::io("Failed to write download chunk", e))?;
+
+        // Progress is only informational, a closed window must not abort the download
+        if let Err(error) = window.emit(
+            "download-progress",
+            DownloadProgress {
+                downloaded,
+                total: total_size,
+            },
+        ) {
+            eprintln!("[download] Failed to emit progress: {}", error);
+        }
     }
 
     output
         .flush()
         .await
-        .map_err(|e| format!("Failed to finalize download file: {e}"))?;
+        .map_err(|e| AppError::io("Failed to finalize download file", e))?;
 
     match detect_archive_format(&temp_path)? {
         Some(_) => {
@@ -528,40 +673,71 @@ pub async fn download_mod(url: String, to: String, window: tauri::Window) -> Res
         None => {
             let file_path = mod_dir.join(&filename);
             fs::rename(&temp_path, &file_path)
-                .map_err(|e| format!("Failed to move downloaded file into place: {e}"))?;
-
-            let mod_json = ModJson {
-                id: Uuid::new_v4().to_string(),
-                url: url.clone(),
-                ..Default::default()
-            };
-            let json = serde_json::to_string_pretty(&mod_json).map_err(|e| e.to_string())?;
-            fs::write(mod_dir.join("mod.json"), json).map_err(|e| e.to_string())?;
+                .map_err(|e| AppError::io("Failed to move downloaded file into place", e))?;
+
+            // Merges into an existing mod.json so reinstalls keep the user's metadata
+            write_mod_metadata(&mod_dir, Some(&url))?;
+            reapply_key_overrides(&mod_dir, false);
         }
     }
 
+    // Reinstalls overwrite files in place and can't be undone
+    if !reinstall {
+        let root = Path::new(&to);
+        record(
+            root,
+            &format!("Download {}", mod_name),
+            vec![created(root, &mod_dir)?],
+        );
+    }
+
     Ok(())
 }
 
 #[tauri::command]
-pub fn add_mod(path: String, to: String) -> Result<(), String> {
+pub fn add_mod(path: String, to: String) -> Result<(), AppError> {
     let mod_source = Path::new(&path);
     let target_dir = Path::new(&to);
 
     if !target_dir.exists() || !target_dir.is_dir() {
-        return Err("Target directory does not exist".to_string());
+        return Err(AppError::not_found("Target directory does not exist"));
     }
 
     if mod_source.exists() && mod_source.is_dir() {
-        let mod_name = mod_source.file_name().ok_or("Invalid mod directory name")?;
+        let mod_name = mod_source.file_name().ok_or_else(|| {
+            AppError::invalid_input("Invalid mod directory name").with_path(mod_source)
+        })?;
         let new_mod_path = target_dir.join(mod_name);
 
         if new_mod_path.exists() {
-            return Err("Mod already exists in the target directory".to_string());
+            return Err(AppError::already_exists(
+                "Mod already exists in the target directory",
+            ));
         }
 
-        copy_dir_recursive(mod_source, &new_mod_path).map_err(|e| e.to_string())?;
-        fs::remove_dir_all(mod_source).map_err(|e| e.to_string())?;
+        copy_dir_recursive(mod_source, &new_mod_path).map_err(|e| {
+            AppError::io(format!("Failed to copy {}", mod_source.display()), e)
+                .with_path(mod_source)
+        })?;
+        fs::remove_dir_all(mod_source).map_err(|e| {
+            AppError::io(format!("Failed to remove {}", mod_source.display()), e)
+                .with_path(mod_source)
+        })?;
+
+        reapply_key_overrides(&new_mod_path, false);
+        if let Err(error) = import_previews(&new_mod_path) {
+            eprintln!("[preview] {}", error);
+        }
+
+        // Undo moves the folder back where it came from
+        record(
+            target_dir,
+            &format!("Add {}", parse_name(&mod_name.to_string_lossy()).1),
+            vec![FsChange::Move {
+                from: mod_source.to_path_buf(),
+                to: new_mod_path,
+            }],
+        );
     } else if mod_source.exists() && mod_source.is_file() {
         let mod_name = mod_source
             .file_stem()
@@ -570,27 +746,41 @@ pub fn add_mod(path: String, to: String) -> Result<(), String> {
         let new_mod_path = create_unique_mod_dir(target_dir, mod_name)?;
 
         extract_archive_to_dir(mod_source, &new_mod_path, None)?;
+
+        record(
+            target_dir,
+            &format!("Add {}", mod_name),
+            vec![created(target_dir, &new_mod_path)?],
+        );
     } else {
-        return Err("Mod source does not exist".to_string());
+        return Err(AppError::not_found("Mod source does not exist"));
     }
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_mod(path: String) -> Result<(), String> {
+pub fn delete_mod(path: String) -> Result<(), AppError> {
     let mod_dir = Path::new(&path);
 
     if !mod_dir.exists() || !mod_dir.is_dir() {
-        return Err("Mod directory does not exist".to_string());
+        return Err(AppError::not_found("Mod directory does not exist"));
     }
 
-    fs::remove_dir_all(mod_dir).map_err(|e| e.to_string())?;
+    // Moved to the trash so it can be restored or undone
+    let root = find_library_root(mod_dir)?;
+    let change = trash_mod(&root, mod_dir)?;
+    let name = mod_dir.file_name().unwrap_or_default().to_string_lossy();
+    record(
+        &root,
+        &format!("Delete {}", parse_name(&name).1),
+        vec![change],
+    );
 
     Ok(())
 }
 
-fn copy_dir_recursive(src: &Path, dst: &Path) -> io::Result<()> {
+pub fn copy_dir_recursive(src: &Path, dst: &Path) -> io::Result<()> {
     if !dst.exists() {
         fs::create_dir_all(dst)?;
     }
@@ -610,7 +800,13 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> io::Result<()> {
     Ok(())
 }
 
-async fn load_auto_match_categories() -> Vec<Category> {
+/// Prefers the live character list and falls back to the bundled list if scraping fails.
+/// A successful scrape is kept for the rest of the session.
+pub async fn load_auto_match_categories() -> Vec<Category> {
+    if let Some(categories) = SCRAPED_CATEGORIES.get() {
+        return categories.clone();
+    }
+
     match scrape_characters().await {
         Ok(characters) if !characters.is_empty() => {
             println!(
@@ -618,13 +814,15 @@ async fn load_auto_match_categories() -> Vec<Category> {
                 characters.len()
             );
 
-            characters
+            let categories = characters
                 .into_iter()
                 .map(|character| Category {
                     name: character.name,
                     icon: character.thumbnail,
                 })
-                .collect()
+                .collect::<Vec<_>>();
+
+            SCRAPED_CATEGORIES.get_or_init(|| categories).clone()
         }
         Ok(_) => {
             eprintln!(
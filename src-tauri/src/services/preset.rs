@@ -4,12 +4,26 @@ use std::fs;
 use std::path::Path;
 use uuid::Uuid;
 
-use crate::services::mod_service::get_folder_mods;
+use crate::services::conflict::{conflicts_involving, HashConflict};
+use crate::services::discovery::ensure_group_enabled;
+use crate::services::enable_state::name_for_state;
+use crate::services::error::AppError;
+use crate::services::index::load_library;
+use crate::services::journal::{read_text, record, FsChange};
+use crate::services::mod_json::{record_enabled, MOD_JSON_FILE_NAME};
+use crate::services::settings::load_settings;
+use crate::services::storage::{read_recovering, write_atomic};
+use crate::services::user_vars::{read_user_vars, user_ini_path, vars_for_mod, write_user_vars};
+
+const PRESETS_FILE_NAME: &str = "presets.json";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModPreset {
     pub name: String,
     pub enabled_mods: Vec<String>,
+    // d3dx_user.ini values of the enabled mods, restored when the preset is applied
+    #[serde(default)]
+    pub user_vars: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -18,18 +32,13 @@ pub struct Presets {
 }
 
 #[tauri::command]
-pub fn save_preset(path: String, preset_name: String, enabled_mods: Vec<String>) -> Result<(), String> {
+pub async fn save_preset(
+    path: String,
+    preset_name: String,
+    enabled_mods: Vec<String>,
+) -> Result<(), AppError> {
     let dir = Path::new(&path);
-    let presets_path = dir.join("presets.json");
-
-    let mut presets = if presets_path.exists() {
-        let content = fs::read_to_string(&presets_path)
-            .map_err(|e| format!("Failed to read presets.json: {}", e))?;
-        serde_json::from_str::<Presets>(&content)
-            .map_err(|e| format!("Failed to parse presets.json: {}", e))?
-    } else {
-        Presets::default()
-    };
+    let mut presets = read_presets(dir)?.unwrap_or_default();
 
     let existing_preset_id = presets
         .presets
@@ -38,85 +47,58 @@ pub fn save_preset(path: String, preset_name: String, enabled_mods: Vec<String>)
         .map(|(id, _)| id.clone());
 
     let preset_id = existing_preset_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let user_vars = capture_user_vars(dir, &enabled_mods).await?;
     let new_preset = ModPreset {
         name: preset_name,
         enabled_mods,
+        user_vars,
     };
 
+    let label = format!("Save preset {}", new_preset.name);
     presets.presets.insert(preset_id, new_preset);
-
-    let json = serde_json::to_string_pretty(&presets)
-        .map_err(|e| format!("Failed to serialize presets: {}", e))?;
-    fs::write(&presets_path, json).map_err(|e| format!("Failed to write presets.json: {}", e))?;
-
-    Ok(())
+    write_presets(dir, &presets, &label)
 }
 
 #[tauri::command]
-pub fn get_presets(path: String) -> Result<HashMap<String, ModPreset>, String> {
+pub fn get_presets(path: String) -> Result<HashMap<String, ModPreset>, AppError> {
     let dir = Path::new(&path);
-    let presets_path = dir.join("presets.json");
-
-    if !presets_path.exists() {
-        return Ok(HashMap::new());
-    }
 
-    let content = fs::read_to_string(&presets_path)
-        .map_err(|e| format!("Failed to read presets.json: {}", e))?;
-    let presets: Presets = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse presets.json: {}", e))?;
-
-    Ok(presets.presets)
+    Ok(read_presets(dir)?
+        .map(|presets| presets.presets)
+        .unwrap_or_default())
 }
 
 #[tauri::command]
-pub fn delete_preset(path: String, preset_id: String) -> Result<(), String> {
+pub fn delete_preset(path: String, preset_id: String) -> Result<(), AppError> {
     let dir = Path::new(&path);
-    let presets_path = dir.join("presets.json");
-
-    if !presets_path.exists() {
-        return Err("Presets file does not exist".to_string());
-    }
-
-    let content = fs::read_to_string(&presets_path)
-        .map_err(|e| format!("Failed to read presets.json: {}", e))?;
-    let mut presets: Presets = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse presets.json: {}", e))?;
-
-    if presets.presets.remove(&preset_id).is_none() {
-        return Err("Preset not found".to_string());
-    }
+    let mut presets =
+        read_presets(dir)?.ok_or_else(|| AppError::not_found("Presets file does not exist"))?;
 
-    let json = serde_json::to_string_pretty(&presets)
-        .map_err(|e| format!("Failed to serialize presets: {}", e))?;
-    fs::write(&presets_path, json).map_err(|e| format!("Failed to write presets.json: {}", e))?;
+    let Some(preset) = presets.presets.remove(&preset_id) else {
+        return Err(AppError::not_found("Preset not found"));
+    };
 
-    Ok(())
+    write_presets(dir, &presets, &format!("Delete preset {}", preset.name))
 }
 
 #[tauri::command]
-pub async fn apply_preset(path: String, preset_id: String) -> Result<(), String> {
+pub async fn apply_preset(path: String, preset_id: String) -> Result<Vec<HashConflict>, AppError> {
     println!("Applying preset {} to path {}", preset_id, path);
 
     let dir = Path::new(&path);
-    let presets_path = dir.join("presets.json");
-
-    if !presets_path.exists() {
-        return Err(format!("Presets file not found at {:?}", presets_path));
-    }
-
-    let content = fs::read_to_string(&presets_path)
-        .map_err(|e| format!("Failed to read presets.json: {}", e))?;
+    let presets = read_presets(dir)?.ok_or_else(|| {
+        AppError::not_found(format!(
+            "Presets file not found at {:?}",
+            dir.join(PRESETS_FILE_NAME)
+        ))
+        .with_path(&dir.join(PRESETS_FILE_NAME))
+    })?;
 
     println!("Loaded presets file content");
 
-    let presets: Presets = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse presets.json: {}", e))?;
-
-    let preset = presets
-        .presets
-        .get(&preset_id)
-        .ok_or_else(|| format!("Preset {} not found in presets file", preset_id))?;
+    let preset = presets.presets.get(&preset_id).ok_or_else(|| {
+        AppError::not_found(format!("Preset {} not found in presets file", preset_id))
+    })?;
 
     println!(
         "Found preset '{}' with {} enabled mods",
@@ -124,9 +106,23 @@ pub async fn apply_preset(path: String, preset_id: String) -> Result<(), String>
         preset.enabled_mods.len()
     );
 
-    let mods = get_folder_mods(path.clone()).await?;
+    let mods = load_library(dir).await?;
     println!("Found {} total mods in directory", mods.len());
 
+    // Checked before anything is renamed, rather than leaving these mods off silently
+    for mod_entry in mods
+        .iter()
+        .filter(|mod_entry| !mod_entry.enabled && preset.enabled_mods.contains(&mod_entry.id))
+    {
+        ensure_group_enabled(dir, Path::new(&mod_entry.path))
+            .map_err(|error| error.with_mod_id(&mod_entry.id))?;
+    }
+
+    let settings = load_settings(dir);
+    let label = format!("Apply preset {}", preset.name);
+    let mut newly_enabled = Vec::new();
+    let mut changes = Vec::new();
+
     for mod_entry in mods {
         let mod_path = Path::new(&mod_entry.path);
         println!("Processing mod: {} (ID: {})", mod_entry.name, mod_entry.id);
@@ -160,19 +156,7 @@ pub async fn apply_preset(path: String, preset_id: String) -> Result<(), String>
             }
         };
 
-        let new_name = if should_be_enabled {
-            if current_name.starts_with("disabled ") {
-                current_name[9..].to_string()
-            } else {
-                current_name.clone()
-            }
-        } else {
-            if !current_name.starts_with("disabled ") {
-                format!("disabled {}", current_name)
-            } else {
-                current_name.clone()
-            }
-        };
+        let new_name = name_for_state(&current_name, should_be_enabled, settings.disabled_prefix);
 
         if current_name != new_name {
             if let Some(parent) = mod_path.parent() {
@@ -188,16 +172,36 @@ pub async fn apply_preset(path: String, preset_id: String) -> Result<(), String>
                 }
 
                 match fs::rename(mod_path, &new_path) {
-                    Ok(_) => println!("Successfully renamed mod directory"),
+                    Ok(_) => {
+                        println!("Successfully renamed mod directory");
+                        changes.push(FsChange::Move {
+                            from: mod_path.to_path_buf(),
+                            to: new_path.clone(),
+                        });
+                        if should_be_enabled {
+                            let mod_json_path = new_path.join(MOD_JSON_FILE_NAME);
+                            let before = read_text(&mod_json_path);
+                            record_enabled(&new_path);
+                            changes.push(FsChange::write(&mod_json_path, before));
+                            newly_enabled.push(mod_entry.id.clone());
+                        }
+                    }
                     Err(e) => {
                         println!(
                             "Failed to rename mod directory from {:?} to {:?}: {}",
                             mod_path, new_path, e
                         );
-                        return Err(format!(
-                            "Failed to rename mod '{}' while applying preset: {}",
-                            current_name, e
-                        ));
+                        // The mods renamed so far can still be undone
+                        record(dir, &label, changes);
+                        return Err(AppError::io(
+                            format!(
+                                "Failed to rename mod '{}' while applying preset",
+                                current_name
+                            ),
+                            e,
+                        )
+                        .with_path(mod_path)
+                        .with_mod_id(&mod_entry.id));
                     }
                 }
             } else {
@@ -206,6 +210,72 @@ pub async fn apply_preset(path: String, preset_id: String) -> Result<(), String>
         }
     }
 
+    if !preset.user_vars.is_empty() {
+        let updates = preset
+            .user_vars
+            .iter()
+            .map(|(name, value)| (name.clone(), Some(value.clone())))
+            .collect::<HashMap<_, _>>();
+        let user_ini = user_ini_path(dir);
+        let before = read_text(&user_ini);
+        if let Err(error) = write_user_vars(dir, &updates) {
+            record(dir, &label, changes);
+            return Err(error);
+        }
+        changes.push(FsChange::write(&user_ini, before));
+        println!("Restored {} persisted variables", updates.len());
+    }
+
+    record(dir, &label, changes);
+
     println!("Successfully applied preset {}", preset_id);
+
+    let conflicts = conflicts_involving(dir, &newly_enabled).await;
+    if !conflicts.is_empty() {
+        println!("Preset {} enabled mods with {} hash conflicts", preset_id, conflicts.len());
+    }
+
+    Ok(conflicts)
+}
+
+// None when there is no presets.json nor a good copy of it to recover
+fn read_presets(dir: &Path) -> Result<Option<Presets>, AppError> {
+    let Some(content) = read_recovering(&dir.join(PRESETS_FILE_NAME), |content| {
+        serde_json::from_str::<Presets>(content).is_ok()
+    }) else {
+        return Ok(None);
+    };
+
+    serde_json::from_str(&content).map(Some).map_err(|e| {
+        AppError::invalid_data(format!("Failed to parse presets.json: {}", e))
+            .with_path(&dir.join(PRESETS_FILE_NAME))
+    })
+}
+
+// Saves presets.json as an undoable step named `label`
+fn write_presets(dir: &Path, presets: &Presets, label: &str) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(presets)?;
+    let presets_path = dir.join(PRESETS_FILE_NAME);
+    let before = read_text(&presets_path);
+    write_atomic(&presets_path, json)?;
+    record(dir, label, vec![FsChange::write(&presets_path, before)]);
     Ok(())
 }
+
+async fn capture_user_vars(
+    dir: &Path,
+    enabled_mods: &[String],
+) -> Result<HashMap<String, String>, AppError> {
+    let vars = read_user_vars(dir)?;
+    if vars.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    Ok(load_library(dir)
+        .await?
+        .iter()
+        .filter(|mod_data| enabled_mods.contains(&mod_data.id))
+        .flat_map(|mod_data| vars_for_mod(dir, mod_data, &vars))
+        .map(|var| (var.name, var.value))
+        .collect())
+}
@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::enable_state::{is_disabled_name, parse_name};
+use crate::services::error::AppError;
+use crate::services::ini::relative_to;
+use crate::services::journal::{created, record, stash};
+use crate::services::mod_json::{read_mod_json, save_mod_json};
+use crate::services::storage::find_library_root;
+use crate::services::texture::{decode_texture, downscale, encode_png};
+
+pub const THUMBNAIL_FILE_NAME: &str = "thumbnail.png";
+pub const THUMBNAIL_SIZE: u32 = 512;
+
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "webp"];
+// File names GameBanana uploaders commonly use for their preview shots
+const PREVIEW_NAME_HINTS: [&str; 5] = ["preview", "cover", "thumbnail", "thumb", "showcase"];
+const PREVIEW_FOLDER_HINTS: [&str; 4] = ["screenshot", "preview", "image", "showcase"];
+// Archives sometimes nest the mod a couple of levels deep
+const MAX_DEPTH: usize = 3;
+
+/// Picks the mod's preview images after an install: the best one becomes `thumbnail.png`
+/// and the others are listed as the mod.json gallery. A mod that already has a thumbnail
+/// keeps it.
+pub fn import_previews(mod_dir: &Path) -> Result<(), AppError> {
+    let previews = find_preview_images(mod_dir);
+    if previews.is_empty() {
+        return Ok(());
+    }
+
+    let mut remaining = previews.clone();
+    if !mod_dir.join(THUMBNAIL_FILE_NAME).exists() {
+        // Skip images that fail to decode, e.g. a `.png` that is really a GIF
+        let thumbnail = previews.iter().find_map(|preview| {
+            decode_texture(preview)
+                .map_err(|error| eprintln!("[preview] Skipping {}: {}", preview.display(), error))
+                .ok()
+                .map(|image| (preview, image))
+        });
+
+        if let Some((preview, image)) = thumbnail {
+            save_thumbnail(mod_dir, image)?;
+            remaining.retain(|other| other != preview);
+            println!("[preview] Thumbnail created from {}", preview.display());
+        }
+    }
+
+    let mut mod_json = read_mod_json(mod_dir)?;
+    for preview in remaining {
+        let file = relative_to(mod_dir, &preview);
+        if !mod_json.gallery.contains(&file) {
+            mod_json.gallery.push(file);
+        }
+    }
+    save_mod_json(mod_dir, &mod_json)
+}
+
+/// [`save_thumbnail`] as an undoable step. The previous thumbnail is parked in the journal
+/// stash rather than kept as text.
+pub fn replace_thumbnail(mod_dir: &Path, image: image::RgbaImage) -> Result<(), AppError> {
+    let root = find_library_root(mod_dir)?;
+    let thumbnail = mod_dir.join(THUMBNAIL_FILE_NAME);
+    let mut changes = stash(&root, &thumbnail)?.into_iter().collect::<Vec<_>>();
+
+    if let Err(error) = save_thumbnail(mod_dir, image) {
+        for change in &changes {
+            if let Err(restore_error) = change.revert() {
+                eprintln!("[preview] Failed to restore thumbnail: {}", restore_error);
+            }
+        }
+        return Err(error);
+    }
+    changes.push(created(&root, &thumbnail)?);
+
+    let folder_name = mod_dir.file_name().unwrap_or_default().to_string_lossy();
+    record(
+        &root,
+        &format!("Change thumbnail of {}", parse_name(&folder_name).1),
+        changes,
+    );
+    Ok(())
+}
+
+/// Writes `image` as the mod's `thumbnail.png`, downscaled to the standard size.
+pub fn save_thumbnail(mod_dir: &Path, image: image::RgbaImage) -> Result<(), AppError> {
+    let png = encode_png(&downscale(image, THUMBNAIL_SIZE))?;
+    fs::write(mod_dir.join(THUMBNAIL_FILE_NAME), png)
+        .map_err(|e| AppError::io("Failed to write thumbnail", e))
+}
+
+/// Likely preview images in the mod folder, best candidates first.
+pub fn find_preview_images(mod_dir: &Path) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    collect_candidates(mod_dir, 0, false, &mut candidates);
+
+    candidates.sort_by(|(rank_a, path_a), (rank_b, path_b)| {
+        rank_a.cmp(rank_b).then_with(|| path_a.cmp(path_b))
+    });
+    candidates.into_iter().map(|(_, path)| path).collect()
+}
+
+// Rank 0: named like a preview, 1: inside a screenshots-like folder, 2: loose image in
+// the mod root. Other images are most likely textures and are left out.
+fn collect_candidates(
+    dir: &Path,
+    depth: usize,
+    in_preview_folder: bool,
+    candidates: &mut Vec<(usize, PathBuf)>,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_ascii_lowercase();
+
+        if is_disabled_name(&name) || name.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            if depth + 1 < MAX_DEPTH {
+                let preview_folder = in_preview_folder
+                    || PREVIEW_FOLDER_HINTS.iter().any(|hint| name.contains(hint));
+                collect_candidates(&path, depth + 1, preview_folder, candidates);
+            }
+            continue;
+        }
+
+        if name == THUMBNAIL_FILE_NAME || !is_image_file(&path) {
+            continue;
+        }
+
+        let rank = if PREVIEW_NAME_HINTS.iter().any(|hint| name.contains(hint)) {
+            0
+        } else if in_preview_folder {
+            1
+        } else if depth == 0 {
+            2
+        } else {
+            continue;
+        };
+        candidates.push((rank, path));
+    }
+}
+
+fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_named_previews_before_screenshots_and_skips_textures() {
+        let root = std::env::temp_dir().join(format!("wuwa-preview-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("Screenshots")).unwrap();
+        fs::create_dir_all(root.join("Textures")).unwrap();
+        for file in [
+            "Screenshots/1.jpg",
+            "Textures/Body.png",
+            "Preview.PNG",
+            "extra.webp",
+            "thumbnail.png",
+        ] {
+            fs::write(root.join(file), b"").unwrap();
+        }
+
+        let previews = find_preview_images(&root)
+            .iter()
+            .map(|path| relative_to(&root, path))
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            previews,
+            vec!["Preview.PNG", "Screenshots/1.jpg", "extra.webp"]
+        );
+    }
+    #[test]
+    fn undoes_a_replaced_thumbnail() {
+        let root = std::env::temp_dir().join(format!("wuwa-thumbnail-{}", uuid::Uuid::new_v4()));
+        let root_path = root.to_string_lossy().to_string();
+        let mod_dir = root.join("Alpha");
+        fs::create_dir_all(crate::services::storage::state_dir(&root)).unwrap();
+        fs::create_dir_all(&mod_dir).unwrap();
+        save_thumbnail(&mod_dir, image::RgbaImage::new(64, 32)).unwrap();
+
+        replace_thumbnail(&mod_dir, image::RgbaImage::new(16, 16)).unwrap();
+        let thumbnail = mod_dir.join(THUMBNAIL_FILE_NAME);
+        let replaced = image::image_dimensions(&thumbnail).unwrap();
+        let undone = crate::services::journal::undo_last(root_path.clone()).unwrap();
+        let restored = image::image_dimensions(&thumbnail).unwrap();
+        crate::services::journal::redo(root_path).unwrap();
+        let redone = image::image_dimensions(&thumbnail).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(undone, "Change thumbnail of Alpha");
+        assert_eq!(replaced, (16, 16));
+        assert_eq!(restored, (64, 32));
+        assert_eq!(redone, (16, 16));
+    }
+}
@@ -0,0 +1,418 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::path::Path;
+
+use crate::services::error::AppError;
+use crate::services::index::load_library;
+use crate::services::mod_service::Mod;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Name,
+    Author,
+    Category,
+    Version,
+    Enabled,
+    Favorite,
+    Rating,
+    Installed,
+    LastEnabled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    // Bare words, matched against name, author, description, category and tags
+    Text(String),
+    Name(String),
+    Author(String),
+    Description(String),
+    Category(String),
+    Version(String),
+    Group(String),
+    Tag(String),
+    Enabled(bool),
+    Favorite(bool),
+    Rating(Ordering, bool, u8),
+    Has(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ModQuery {
+    terms: Vec<Term>,
+    sort: SortKey,
+    descending: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModPage {
+    pub mods: Vec<Mod>,
+    // Matches across all pages
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// Filters and sorts the library index with a search query such as
+/// `category:Jinhsi enabled:false tag:"nsfw free" rating:>=4 sort:installed-desc summer`.
+/// Pages start at 0.
+#[tauri::command]
+pub async fn query_mods(
+    path: String,
+    query: String,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> Result<ModPage, AppError> {
+    let dir = Path::new(&path);
+
+    if !dir.exists() || !dir.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let query = parse_query(&query)?;
+    let mods = load_library(dir).await?;
+
+    let page = page.unwrap_or(0);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let matches = run_query(&query, mods);
+    let total = matches.len();
+    let mods = matches
+        .into_iter()
+        .skip(page.saturating_mul(page_size))
+        .take(page_size)
+        .collect();
+
+    Ok(ModPage {
+        mods,
+        total,
+        page,
+        page_size,
+    })
+}
+
+fn run_query(query: &ModQuery, mods: Vec<Mod>) -> Vec<Mod> {
+    let mut matches = mods
+        .into_iter()
+        .filter(|mod_data| {
+            query
+                .terms
+                .iter()
+                .all(|term| matches(mod_data, &term.condition) != term.negated)
+        })
+        .collect::<Vec<_>>();
+
+    matches.sort_by(|a, b| {
+        let ordering = compare(a, b, query.sort);
+        let ordering = if query.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        // Keep pages stable between calls
+        ordering
+            .then_with(|| compare(a, b, SortKey::Name))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    matches
+}
+
+fn parse_query(query: &str) -> Result<ModQuery, AppError> {
+    let mut parsed = ModQuery {
+        terms: Vec::new(),
+        sort: SortKey::Name,
+        descending: false,
+    };
+
+    for (negated, key, value) in tokenize(query)? {
+        let Some(key) = key else {
+            parsed.terms.push(Term {
+                negated,
+                condition: Condition::Text(value.to_lowercase()),
+            });
+            continue;
+        };
+
+        let key = key.to_lowercase();
+        if key == "sort" {
+            (parsed.sort, parsed.descending) = parse_sort(&value)?;
+            continue;
+        }
+
+        let text = value.to_lowercase();
+        let condition = match key.as_str() {
+            "name" => Condition::Name(text),
+            "author" => Condition::Author(text),
+            "description" | "desc" => Condition::Description(text),
+            "category" | "character" => Condition::Category(text),
+            "version" => Condition::Version(text),
+            "group" | "folder" => Condition::Group(text),
+            "tag" => Condition::Tag(text),
+            "enabled" => Condition::Enabled(parse_bool(&key, &text)?),
+            "disabled" => Condition::Enabled(!parse_bool(&key, &text)?),
+            "favorite" | "fav" => Condition::Favorite(parse_bool(&key, &text)?),
+            "rating" => parse_rating(&text)?,
+            "has" => match text.as_str() {
+                "thumbnail" | "tags" | "notes" | "url" | "rating" | "gallery" => {
+                    Condition::Has(text)
+                }
+                _ => {
+                    return Err(AppError::invalid_input(format!(
+                        "Unknown has: value {}",
+                        value
+                    )))
+                }
+            },
+            _ => {
+                return Err(AppError::invalid_input(format!(
+                    "Unknown search field {}",
+                    key
+                )))
+            }
+        };
+
+        parsed.terms.push(Term { negated, condition });
+    }
+
+    Ok(parsed)
+}
+
+// Splits on whitespace outside quotes into (negated, field, value). A field is whatever
+// precedes the first unquoted colon, so `author:"Some One"` and `"re:zero"` both work.
+fn tokenize(query: &str) -> Result<Vec<(bool, Option<String>, String)>, AppError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    loop {
+        while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut key = None;
+        let mut current = String::new();
+        let mut quoted = false;
+        let mut was_quoted = false;
+
+        while let Some(ch) = chars.next_if(|ch| quoted || !ch.is_whitespace()) {
+            match ch {
+                '"' => {
+                    quoted = !quoted;
+                    was_quoted = true;
+                }
+                ':' if !quoted && key.is_none() && !was_quoted && !current.is_empty() => {
+                    key = Some(std::mem::take(&mut current));
+                }
+                _ => current.push(ch),
+            }
+        }
+
+        if quoted {
+            return Err(AppError::invalid_input("Unterminated quote in search"));
+        }
+        if key.is_some() || !current.is_empty() {
+            tokens.push((negated, key, current));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_sort(value: &str) -> Result<(SortKey, bool), AppError> {
+    let value = value.to_lowercase();
+    // Field names may contain dashes themselves, as in `last-enabled`
+    let (field, descending) = match value.rsplit_once('-') {
+        Some((field, "asc")) => (field, false),
+        Some((field, "desc")) => (field, true),
+        _ => (value.as_str(), false),
+    };
+
+    let sort = match field {
+        "name" => SortKey::Name,
+        "author" => SortKey::Author,
+        "category" | "character" => SortKey::Category,
+        "version" => SortKey::Version,
+        "enabled" | "status" => SortKey::Enabled,
+        "favorite" | "fav" => SortKey::Favorite,
+        "rating" => SortKey::Rating,
+        "installed" => SortKey::Installed,
+        "lastenabled" | "last_enabled" | "last-enabled" | "used" => SortKey::LastEnabled,
+        _ => {
+            return Err(AppError::invalid_input(format!(
+                "Unknown sort field {}",
+                field
+            )))
+        }
+    };
+
+    Ok((sort, descending))
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, AppError> {
+    match value {
+        "true" | "yes" | "1" | "on" => Ok(true),
+        "false" | "no" | "0" | "off" => Ok(false),
+        _ => Err(AppError::invalid_input(format!(
+            "{} expects true or false, got {}",
+            key, value
+        ))),
+    }
+}
+
+// `rating:4`, `rating:>=3`, `rating:<5`; unrated mods never match
+fn parse_rating(value: &str) -> Result<Condition, AppError> {
+    let (ordering, or_equal, stars) = if let Some(stars) = value.strip_prefix(">=") {
+        (Ordering::Greater, true, stars)
+    } else if let Some(stars) = value.strip_prefix("<=") {
+        (Ordering::Less, true, stars)
+    } else if let Some(stars) = value.strip_prefix('>') {
+        (Ordering::Greater, false, stars)
+    } else if let Some(stars) = value.strip_prefix('<') {
+        (Ordering::Less, false, stars)
+    } else {
+        (Ordering::Equal, true, value)
+    };
+
+    let stars = stars
+        .parse::<u8>()
+        .map_err(|_| AppError::invalid_input(format!("rating expects a number, got {}", value)))?;
+    Ok(Condition::Rating(ordering, or_equal, stars))
+}
+
+fn matches(mod_data: &Mod, condition: &Condition) -> bool {
+    let contains = |field: &str, text: &str| field.to_lowercase().contains(text);
+
+    match condition {
+        Condition::Text(text) => {
+            contains(&mod_data.name, text)
+                || contains(&mod_data.author, text)
+                || contains(&mod_data.description, text)
+                || contains(&mod_data.category, text)
+                || mod_data.tags.iter().any(|tag| contains(tag, text))
+        }
+        Condition::Name(text) => contains(&mod_data.name, text),
+        Condition::Author(text) => contains(&mod_data.author, text),
+        Condition::Description(text) => contains(&mod_data.description, text),
+        Condition::Category(text) => mod_data.category.to_lowercase() == *text,
+        Condition::Version(text) => mod_data.version.to_lowercase() == *text,
+        Condition::Group(text) => contains(&mod_data.group, text),
+        Condition::Tag(text) => mod_data.tags.iter().any(|tag| tag.to_lowercase() == *text),
+        Condition::Enabled(enabled) => mod_data.enabled == *enabled,
+        Condition::Favorite(favorite) => mod_data.favorite == *favorite,
+        Condition::Rating(ordering, or_equal, stars) => mod_data.rating.is_some_and(|rating| {
+            let compared = rating.cmp(stars);
+            compared == *ordering || (*or_equal && compared == Ordering::Equal)
+        }),
+        Condition::Has(field) => match field.as_str() {
+            "thumbnail" => !mod_data.thumbnail.is_empty(),
+            "tags" => !mod_data.tags.is_empty(),
+            "notes" => !mod_data.notes.trim().is_empty(),
+            "url" => !mod_data.url.is_empty(),
+            "rating" => mod_data.rating.is_some(),
+            _ => !mod_data.gallery.is_empty(),
+        },
+    }
+}
+
+fn compare(a: &Mod, b: &Mod, sort: SortKey) -> Ordering {
+    let text = |value: &str| value.to_lowercase();
+
+    match sort {
+        SortKey::Name => text(&a.name).cmp(&text(&b.name)),
+        SortKey::Author => text(&a.author).cmp(&text(&b.author)),
+        SortKey::Category => text(&a.category).cmp(&text(&b.category)),
+        SortKey::Version => compare_versions(&a.version, &b.version),
+        SortKey::Enabled => a.enabled.cmp(&b.enabled),
+        SortKey::Favorite => a.favorite.cmp(&b.favorite),
+        SortKey::Rating => a.rating.cmp(&b.rating),
+        SortKey::Installed => a.installed_at.cmp(&b.installed_at),
+        SortKey::LastEnabled => a.last_enabled_at.cmp(&b.last_enabled_at),
+    }
+}
+
+// Numeric per dot-separated part, so 1.10 sorts after 1.9
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |version: &str| {
+        version
+            .trim_start_matches(['v', 'V'])
+            .split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    parts(a).cmp(&parts(b)).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mod_with(name: &str, category: &str, enabled: bool, tags: &[&str], installed: u64) -> Mod {
+        serde_json::from_value(json!({
+            "id": name,
+            "author": "XYZ",
+            "description": "",
+            "version": "",
+            "category": category,
+            "url": "",
+            "name": name,
+            "path": format!("/mods/{}", name),
+            "thumbnail": "",
+            "enabled": enabled,
+            "tags": tags,
+            "installedAt": installed,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn filters_and_sorts_with_quoted_fields_and_negation() {
+        let mods = vec![
+            mod_with("Summer", "Jinhsi", false, &["nsfw-free"], 1),
+            mod_with("Winter", "Jinhsi", false, &["nsfw-free"], 3),
+            mod_with("Night", "Jinhsi", true, &["nsfw-free"], 2),
+            mod_with("Beach", "Changli", false, &[], 4),
+        ];
+
+        let query = parse_query(
+            r#"category:jinhsi enabled:false tag:NSFW-free author:"XYZ" sort:installed-desc"#,
+        )
+        .unwrap();
+        let names = run_query(&query, mods.clone())
+            .into_iter()
+            .map(|mod_data| mod_data.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Winter", "Summer"]);
+
+        let query = parse_query("-category:Jinhsi \"be\"").unwrap();
+        assert_eq!(run_query(&query, mods)[0].name, "Beach");
+
+        assert!(parse_query("categroy:Jinhsi").is_err());
+        assert!(parse_query("sort:name-up").is_err());
+        assert!(parse_query("author:\"XYZ").is_err());
+    }
+    #[test]
+    fn parses_sort_fields_containing_dashes() {
+        assert_eq!(
+            parse_sort("last-enabled").unwrap(),
+            (SortKey::LastEnabled, false)
+        );
+        assert_eq!(
+            parse_sort("Last-Enabled-desc").unwrap(),
+            (SortKey::LastEnabled, true)
+        );
+        assert_eq!(parse_sort("rating-asc").unwrap(), (SortKey::Rating, false));
+    }
+}
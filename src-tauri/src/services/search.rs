@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::services::error::AppError;
+use crate::services::index::{fingerprint, load_library, Fingerprint};
+use crate::services::ini::{find_ini_files, is_excluded};
+use crate::services::mod_service::Mod;
+use crate::services::storage::{state_file, write_atomic};
+
+const SEARCH_INDEX_FILE_NAME: &str = "search.json";
+// Bump whenever tokenizing or weighting changes so the index is rebuilt
+const SEARCH_INDEX_VERSION: u32 = 1;
+const DEFAULT_LIMIT: usize = 50;
+
+const README_EXTENSIONS: [&str; 2] = ["txt", "md"];
+// Anything bigger is a log or a dump rather than a readme
+const MAX_README_BYTES: u64 = 256 * 1024;
+
+// A word in the name says more about a mod than one in an ini comment
+const NAME_WEIGHT: f32 = 4.0;
+const TAG_WEIGHT: f32 = 3.0;
+const META_WEIGHT: f32 = 2.0;
+const TEXT_WEIGHT: f32 = 1.0;
+// Query words also match longer indexed words, e.g. "hairpin" finds "hairpins"
+const PREFIX_MATCH_FACTOR: f32 = 0.5;
+const MIN_PREFIX_LENGTH: usize = 3;
+
+// BM25 parameters
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+const STOP_WORDS: [&str; 12] = [
+    "a", "an", "and", "by", "for", "in", "is", "it", "of", "on", "the", "to",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchDocument {
+    fingerprint: Fingerprint,
+    mod_id: String,
+    // Weighted term frequencies
+    terms: HashMap<String, f32>,
+    length: f32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIndex {
+    version: u32,
+    // Keyed by the absolute mod folder path, like the library index
+    documents: HashMap<String, SearchDocument>,
+}
+
+/// Full-text search over mod.json fields, readmes and ini comments. Returns mod ids, best
+/// match first; mods matching more of the words always rank above those matching fewer.
+#[tauri::command]
+pub async fn search_mods(
+    path: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<String>, AppError> {
+    let dir = Path::new(&path);
+
+    if !dir.exists() || !dir.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let mods = load_library(dir).await?;
+    let index = refresh_search_index(dir, &mods);
+
+    Ok(rank(&index, &query, limit.unwrap_or(DEFAULT_LIMIT)))
+}
+
+/// Brings the saved search index in line with `mods`. Run whenever the library is listed so
+/// searching rarely has to index anything itself.
+pub fn refresh_search_index(root: &Path, mods: &[Mod]) -> SearchIndex {
+    let mut index = read_search_index(root);
+
+    if update_search_index(&mut index, mods) {
+        if let Err(error) = write_search_index(root, &index) {
+            eprintln!("[search] Failed to save search index: {}", error);
+        }
+    }
+
+    index
+}
+
+// Re-indexes mods whose folder changed and drops removed ones. Returns whether anything changed.
+fn update_search_index(index: &mut SearchIndex, mods: &[Mod]) -> bool {
+    let mut documents = HashMap::with_capacity(mods.len());
+    let mut refreshed = 0;
+
+    for mod_data in mods {
+        let current = fingerprint(Path::new(&mod_data.path));
+
+        let document = match index.documents.remove(&mod_data.path) {
+            Some(document) if document.fingerprint == current && document.mod_id == mod_data.id => {
+                document
+            }
+            _ => {
+                refreshed += 1;
+                index_mod(mod_data, current)
+            }
+        };
+
+        documents.insert(mod_data.path.clone(), document);
+    }
+
+    let removed = index.documents.len();
+    index.documents = documents;
+
+    if refreshed > 0 || removed > 0 || index.version != SEARCH_INDEX_VERSION {
+        println!(
+            "[search] Indexed {} mods, dropped {} stale entries",
+            refreshed, removed
+        );
+        index.version = SEARCH_INDEX_VERSION;
+        return true;
+    }
+    false
+}
+
+fn index_mod(mod_data: &Mod, fingerprint: Fingerprint) -> SearchDocument {
+    let mod_dir = Path::new(&mod_data.path);
+    let mut terms = HashMap::new();
+
+    add_terms(&mut terms, &mod_data.name, NAME_WEIGHT);
+    for tag in &mod_data.tags {
+        add_terms(&mut terms, tag, TAG_WEIGHT);
+    }
+    add_terms(&mut terms, &mod_data.author, META_WEIGHT);
+    add_terms(&mut terms, &mod_data.category, META_WEIGHT);
+    add_terms(&mut terms, &mod_data.description, TEXT_WEIGHT);
+    add_terms(&mut terms, &mod_data.notes, TEXT_WEIGHT);
+
+    for readme in readme_texts(mod_dir) {
+        add_terms(&mut terms, &readme, TEXT_WEIGHT);
+    }
+    for comment in ini_comments(mod_dir) {
+        add_terms(&mut terms, &comment, TEXT_WEIGHT);
+    }
+
+    SearchDocument {
+        fingerprint,
+        mod_id: mod_data.id.clone(),
+        length: terms.values().sum(),
+        terms,
+    }
+}
+
+fn add_terms(terms: &mut HashMap<String, f32>, text: &str, weight: f32) {
+    for term in tokenize(text) {
+        *terms.entry(term).or_insert(0.0) += weight;
+    }
+}
+
+// Lowercased words, split on anything that is not a letter or digit
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+// `.txt` and `.md` files next to the mod's files, or anything named like a readme
+fn readme_texts(mod_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(mod_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| !is_excluded(path))
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            let extension = path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            name.starts_with("readme") || README_EXTENSIONS.contains(&extension.as_str())
+        })
+        .filter(|path| {
+            fs::metadata(path)
+                .map(|metadata| metadata.is_file() && metadata.len() <= MAX_README_BYTES)
+                .unwrap_or(false)
+        })
+        .filter_map(|path| fs::read(path).ok())
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+        .collect()
+}
+
+// `;` comment lines of every loaded ini, where authors describe toggles and keys
+fn ini_comments(mod_dir: &Path) -> Vec<String> {
+    find_ini_files(mod_dir)
+        .into_iter()
+        .filter_map(|file| fs::read(file).ok())
+        .flat_map(|bytes| {
+            String::from_utf8_lossy(&bytes)
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix(';'))
+                .map(|comment| comment.trim_start_matches(';').to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// BM25 over the weighted term frequencies
+fn rank(index: &SearchIndex, query: &str, limit: usize) -> Vec<String> {
+    let query_terms = tokenize(query)
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    if query_terms.is_empty() || index.documents.is_empty() {
+        return Vec::new();
+    }
+
+    let count = index.documents.len() as f32;
+    let average_length = index
+        .documents
+        .values()
+        .map(|document| document.length)
+        .sum::<f32>()
+        / count;
+
+    // Inverted postings: term -> (mod id, weighted frequency, document length)
+    let mut postings: HashMap<&str, Vec<(&str, f32, f32)>> = HashMap::new();
+    for document in index.documents.values() {
+        for (term, frequency) in &document.terms {
+            postings.entry(term.as_str()).or_default().push((
+                document.mod_id.as_str(),
+                *frequency,
+                document.length,
+            ));
+        }
+    }
+
+    // Mod id -> (query words matched, score)
+    let mut scores: HashMap<&str, (usize, f32)> = HashMap::new();
+    for query_term in &query_terms {
+        let mut term_scores: HashMap<&str, f32> = HashMap::new();
+
+        for (term, documents) in &postings {
+            let factor = if *term == query_term {
+                1.0
+            } else if query_term.len() >= MIN_PREFIX_LENGTH && term.starts_with(query_term.as_str())
+            {
+                PREFIX_MATCH_FACTOR
+            } else {
+                continue;
+            };
+
+            let frequency = documents.len() as f32;
+            let idf = (1.0 + (count - frequency + 0.5) / (frequency + 0.5)).ln();
+            for (mod_id, tf, length) in documents {
+                let norm = tf + K1 * (1.0 - B + B * length / average_length.max(1.0));
+                let score = factor * idf * tf * (K1 + 1.0) / norm;
+                let best = term_scores.entry(mod_id).or_insert(0.0);
+                *best = best.max(score);
+            }
+        }
+
+        for (mod_id, score) in term_scores {
+            let entry = scores.entry(mod_id).or_insert((0, 0.0));
+            entry.0 += 1;
+            entry.1 += score;
+        }
+    }
+
+    let mut ranked = scores.into_iter().collect::<Vec<_>>();
+    ranked.sort_by(
+        |(id_a, (matched_a, score_a)), (id_b, (matched_b, score_b))| {
+            matched_b
+                .cmp(matched_a)
+                .then_with(|| score_b.total_cmp(score_a))
+                .then_with(|| id_a.cmp(id_b))
+        },
+    );
+
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(mod_id, _)| mod_id.to_string())
+        .collect()
+}
+
+fn read_search_index(root: &Path) -> SearchIndex {
+    let Ok(index_path) = state_file(root, SEARCH_INDEX_FILE_NAME) else {
+        return SearchIndex::default();
+    };
+
+    let Ok(content) = fs::read_to_string(&index_path) else {
+        return SearchIndex::default();
+    };
+
+    // Like the library index this is only a cache, rebuilt when unreadable
+    match serde_json::from_str::<SearchIndex>(&content) {
+        Ok(index) if index.version == SEARCH_INDEX_VERSION => index,
+        _ => SearchIndex::default(),
+    }
+}
+
+fn write_search_index(root: &Path, index: &SearchIndex) -> Result<(), AppError> {
+    let index_path = state_file(root, SEARCH_INDEX_FILE_NAME)?;
+    let json = serde_json::to_string(index)?;
+    write_atomic(&index_path, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mod_at(dir: &Path, name: &str, description: &str) -> Mod {
+        serde_json::from_value(json!({
+            "id": name,
+            "author": "",
+            "description": description,
+            "version": "",
+            "category": "",
+            "url": "",
+            "name": name,
+            "path": dir.join(name).to_string_lossy(),
+            "thumbnail": "",
+            "enabled": true,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn ranks_mods_matching_all_words_from_readmes_and_ini_comments() {
+        let root = std::env::temp_dir().join(format!("wuwa-search-{}", uuid::Uuid::new_v4()));
+        for name in ["Dress", "Hat", "Boots"] {
+            fs::create_dir_all(root.join(name)).unwrap();
+        }
+        fs::write(
+            root.join("Dress").join("Dress.ini"),
+            "; Press 3 to toggle the hairpins\n[KeySwap]\nkey = 3\n",
+        )
+        .unwrap();
+        fs::write(root.join("Hat").join("README.md"), "Hairpin included").unwrap();
+        fs::write(root.join("Boots").join("Boots.ini"), "; toggle: 4\n").unwrap();
+
+        let mods = vec![
+            mod_at(&root, "Dress", ""),
+            mod_at(&root, "Hat", "A hat"),
+            mod_at(&root, "Boots", ""),
+        ];
+        let mut index = SearchIndex::default();
+        update_search_index(&mut index, &mods);
+
+        let ranked = rank(&index, "hairpin toggle", 10);
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0], "Dress");
+        assert_eq!(rank(&index, "included", 10), vec!["Hat"]);
+
+        assert!(update_search_index(&mut index, &mods[..1]));
+        assert_eq!(index.documents.len(), 1);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
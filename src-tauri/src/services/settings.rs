@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::services::enable_state::DisabledPrefix;
+use crate::services::error::AppError;
+use crate::services::storage::{read_recovering, state_file, write_atomic};
+
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Per-library preferences, stored with the library so every machine shares them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LibrarySettings {
+    pub disabled_prefix: DisabledPrefix,
+    // Trashed mods older than this are purged, 0 keeps them until purged by hand
+    pub trash_max_age_days: u32,
+    // Oldest trashed mods are purged once the trash grows past this, 0 for no limit
+    pub trash_max_size_mb: u64,
+}
+
+impl Default for LibrarySettings {
+    fn default() -> Self {
+        LibrarySettings {
+            disabled_prefix: DisabledPrefix::default(),
+            trash_max_age_days: 30,
+            trash_max_size_mb: 0,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_library_settings(path: String) -> Result<LibrarySettings, AppError> {
+    Ok(load_settings(Path::new(&path)))
+}
+
+#[tauri::command]
+pub fn set_library_settings(path: String, settings: LibrarySettings) -> Result<(), AppError> {
+    save_settings(Path::new(&path), &settings)
+}
+
+pub fn load_settings(root: &Path) -> LibrarySettings {
+    let Ok(settings_path) = state_file(root, SETTINGS_FILE_NAME) else {
+        return LibrarySettings::default();
+    };
+
+    let content = read_recovering(&settings_path, |content| {
+        serde_json::from_str::<LibrarySettings>(content).is_ok()
+    });
+
+    match content {
+        Some(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("[settings] Ignoring unreadable settings.json: {}", e);
+            LibrarySettings::default()
+        }),
+        None => LibrarySettings::default(),
+    }
+}
+
+pub fn save_settings(root: &Path, settings: &LibrarySettings) -> Result<(), AppError> {
+    let settings_path = state_file(root, SETTINGS_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(settings)?;
+    write_atomic(&settings_path, json)
+}
@@ -0,0 +1,232 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::services::error::AppError;
+use crate::services::mod_json::now_millis;
+
+// Manager-owned files live in a hidden folder inside the Mods directory so they travel with
+// the library. Discovery skips dot-folders, so nothing in here is ever listed as a mod.
+pub const STATE_DIR_NAME: &str = ".wuwamoma";
+
+pub fn state_dir(root: &Path) -> PathBuf {
+    root.join(STATE_DIR_NAME)
+}
+
+/// Path of a manager file inside the state folder, creating the folder if needed.
+pub fn state_file(root: &Path, name: &str) -> Result<PathBuf, AppError> {
+    let dir = state_dir(root);
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Failed to create {}", dir.display()), e))?;
+    Ok(dir.join(name))
+}
+
+/// Library a mod folder (or a file in it) belongs to: the closest ancestor that has a state
+/// folder. Listing a library creates that folder, so this only fails for paths outside any
+/// library that was opened. Guessing would pick a group folder as the root for nested mods.
+pub fn find_library_root(path: &Path) -> Result<PathBuf, AppError> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| state_dir(dir).is_dir())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            AppError::not_found(format!("{} is not inside a Mods folder", path.display()))
+                .with_path(path)
+        })
+}
+
+/// `file` inside `mod_dir`, which must exist. Both paths are resolved first: an absolute
+/// `file` replaces the mod folder when joined, and links can point anywhere. `what` names
+/// the file in errors, e.g. `Texture`.
+pub fn resolve_mod_file(mod_dir: &Path, file: &str, what: &str) -> Result<PathBuf, AppError> {
+    let path = mod_dir.join(file);
+
+    if !path.is_file() {
+        return Err(
+            AppError::not_found(format!("{} {} does not exist in the mod", what, file))
+                .with_path(&path),
+        );
+    }
+    let inside = match (path.canonicalize(), mod_dir.canonicalize()) {
+        (Ok(path), Ok(mod_dir)) => path.starts_with(mod_dir),
+        _ => false,
+    };
+    if !inside || file.split(['/', '\\']).any(|part| part == "..") {
+        return Err(
+            AppError::invalid_input(format!("{} must be inside the mod folder", what))
+                .with_path(&path),
+        );
+    }
+
+    Ok(path)
+}
+
+/// Replaces `path` with `contents` without ever leaving it half written: the data goes to a
+/// synced temp file that is renamed over the original. A copy of what was written is kept as
+/// `<name>.bak`, which [`read_recovering`] falls back to. The file being replaced is never
+/// trusted for that copy, it may be the damaged one.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), AppError> {
+    replace_with(path, contents.as_ref())?;
+
+    // The write itself went through, a stale copy only matters if the file breaks later
+    if let Err(error) = replace_with(&backup_path(path), contents.as_ref()) {
+        eprintln!("[storage] {}", error);
+    }
+    Ok(())
+}
+
+/// [`write_atomic`] without the `.bak` copy, for files that keep their own backups such as
+/// rewritten mod inis.
+pub fn replace_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), AppError> {
+    replace_with(path, contents.as_ref())
+}
+
+/// Contents of a file written with [`write_atomic`]. When it is missing or `is_valid` rejects
+/// it (a crash between the two renames, a truncated write by another tool), the last good copy
+/// is put back in its place and returned; the rejected file is kept as `<name>.corrupt-<millis>`.
+/// Without a good copy the file is returned as is, for the caller to report.
+pub fn read_recovering(path: &Path, is_valid: impl Fn(&str) -> bool) -> Option<String> {
+    let contents = fs::read_to_string(path).ok();
+    if contents.as_deref().is_some_and(&is_valid) {
+        return contents;
+    }
+
+    let Some(backup) = fs::read_to_string(backup_path(path))
+        .ok()
+        .filter(|backup| is_valid(backup))
+    else {
+        return contents;
+    };
+
+    if contents.is_some() {
+        let corrupt = sibling(path, &format!("corrupt-{}", now_millis()));
+        if let Err(error) = fs::rename(path, &corrupt) {
+            eprintln!(
+                "[storage] Failed to move {} aside: {}",
+                path.display(),
+                error
+            );
+            return contents;
+        }
+    }
+
+    let temp = sibling(path, &format!("tmp-{}", Uuid::new_v4()));
+    match write_synced(&temp, backup.as_bytes()).and_then(|()| fs::rename(&temp, path)) {
+        Ok(()) => println!(
+            "[storage] Recovered {} from its last good copy",
+            path.display()
+        ),
+        Err(error) => {
+            let _ = fs::remove_file(&temp);
+            eprintln!("[storage] Failed to restore {}: {}", path.display(), error);
+        }
+    }
+    Some(backup)
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+fn replace_with(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    let temp = sibling(path, &format!("tmp-{}", Uuid::new_v4()));
+
+    let replaced = write_synced(&temp, contents)
+        .and_then(|()| fs::rename(&temp, path))
+        .map_err(|e| AppError::io(format!("Failed to write {}", path.display()), e));
+
+    if replaced.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    replaced?;
+    sync_parent(path);
+    Ok(())
+}
+
+// `<name>.<suffix>` next to `path`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}", name, suffix))
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+// Makes the renames themselves durable. Directories can't be opened for this on Windows,
+// where the rename is already flushed with the file.
+fn sync_parent(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Err(error) = File::open(parent).and_then(|dir| dir.sync_all()) {
+            eprintln!("[storage] Failed to sync {}: {}", parent.display(), error);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_last_good_copy_and_recovers_from_it() {
+        let dir = std::env::temp_dir().join(format!("wuwa-storage-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("presets.json");
+        let is_valid = |contents: &str| contents.ends_with('}');
+
+        write_atomic(&path, "{\"v\":1}").unwrap();
+        write_atomic(&path, "{\"v\":2}").unwrap();
+        let backup = fs::read_to_string(backup_path(&path)).unwrap();
+
+        fs::write(&path, "{\"v\":").unwrap();
+        let recovered = read_recovering(&path, is_valid);
+        let restored = fs::read_to_string(&path).unwrap();
+
+        // A damaged file that gets written over never becomes the backup
+        fs::write(&path, "{\"v\":").unwrap();
+        write_atomic(&path, "{\"v\":3}").unwrap();
+        let rewritten_backup = fs::read_to_string(backup_path(&path)).unwrap();
+
+        fs::remove_file(&path).unwrap();
+        let missing = read_recovering(&path, is_valid);
+        let leftovers = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".tmp-"))
+            .count();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(backup, "{\"v\":2}");
+        assert_eq!(recovered.as_deref(), Some("{\"v\":2}"));
+        assert_eq!(restored, "{\"v\":2}");
+        assert_eq!(rewritten_backup, "{\"v\":3}");
+        assert_eq!(missing.as_deref(), Some("{\"v\":3}"));
+        assert_eq!(leftovers, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_mod_files_linked_from_outside_the_mod() {
+        let dir = std::env::temp_dir().join(format!("wuwa-resolve-{}", Uuid::new_v4()));
+        let mod_dir = dir.join("Mod");
+        fs::create_dir_all(&mod_dir).unwrap();
+        fs::write(mod_dir.join("Skin.ini"), "").unwrap();
+        fs::write(dir.join("d3dx.ini"), "").unwrap();
+        std::os::unix::fs::symlink(dir.join("d3dx.ini"), mod_dir.join("Linked.ini")).unwrap();
+
+        let inside = resolve_mod_file(&mod_dir, "Skin.ini", "Ini file");
+        let linked = resolve_mod_file(&mod_dir, "Linked.ini", "Ini file");
+        let parent = resolve_mod_file(&mod_dir, "../d3dx.ini", "Ini file");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(inside.unwrap(), mod_dir.join("Skin.ini"));
+        assert!(linked.is_err());
+        assert!(parent.is_err());
+    }
+}
@@ -0,0 +1,350 @@
+use base64::{engine::general_purpose, Engine as _};
+use ddsfile::{Dds, DxgiFormat};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use crate::services::error::AppError;
+use crate::services::index::modified_millis;
+use crate::services::ini::IniSummary;
+use crate::services::storage::{resolve_mod_file, state_file};
+
+pub const THUMBNAIL_DIR_NAME: &str = "thumbnails";
+const FALLBACK_THUMBNAIL_SIZE: u32 = 512;
+const GRID_THUMBNAIL_SIZE: u32 = 256;
+const PREVIEW_MAX_SIZE: u32 = 2048;
+// Largest texture Direct3D 11 can load, so anything bigger is a broken or hostile header
+const MAX_DDS_SIZE: u32 = 16384;
+
+// Texture names that usually hold the base color, best match first
+const DIFFUSE_HINTS: [&str; 4] = ["diffuse", "albedo", "basecolor", "color"];
+// Textures that are never useful as a picture of the mod
+const NON_DIFFUSE_HINTS: [&str; 5] = ["normal", "lightmap", "mask", "material", "shadow"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DdsEncoding {
+    Bc1,
+    Bc3,
+    Bc7,
+    Rgba,
+    Bgra,
+}
+
+/// Decodes any texture in a mod folder (`.dds` or a regular image) to a base64 PNG,
+/// downscaled to `max_size` on its longest side.
+#[tauri::command]
+pub fn read_texture_preview(
+    path: String,
+    file: String,
+    max_size: Option<u32>,
+) -> Result<String, AppError> {
+    let mod_dir = Path::new(&path);
+    let texture_path = resolve_mod_file(mod_dir, &file, "Texture")?;
+
+    let image = decode_texture(&texture_path)?;
+    let image = downscale(
+        image,
+        max_size.unwrap_or(PREVIEW_MAX_SIZE).min(PREVIEW_MAX_SIZE),
+    );
+
+    Ok(general_purpose::STANDARD.encode(encode_png(&image)?))
+}
+
+/// Decodes `.dds` files with the BC decoders and everything else through `image`.
+pub fn decode_texture(path: &Path) -> Result<RgbaImage, AppError> {
+    let bytes = fs::read(path)
+        .map_err(|e| AppError::io(format!("Failed to read {}", path.display()), e))?;
+
+    let is_dds = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("dds"))
+        .unwrap_or(false);
+
+    if is_dds {
+        decode_dds(&bytes)
+    } else {
+        image::load_from_memory(&bytes)
+            .map(|image| image.to_rgba8())
+            .map_err(|e| {
+                AppError::invalid_data(format!("Failed to decode {}: {e}", path.display()))
+            })
+    }
+}
+
+/// Decodes the first mip level of a BC1, BC3, BC7 or uncompressed 8-bit DDS texture.
+pub fn decode_dds(bytes: &[u8]) -> Result<RgbaImage, AppError> {
+    let dds = Dds::read(Cursor::new(bytes))
+        .map_err(|e| AppError::invalid_data(format!("Invalid DDS file: {e}")))?;
+    let width = dds.get_width();
+    let height = dds.get_height();
+    if width == 0 || height == 0 || width > MAX_DDS_SIZE || height > MAX_DDS_SIZE {
+        return Err(AppError::invalid_data(format!(
+            "Unsupported DDS size {}x{}",
+            width, height
+        )));
+    }
+    let data = dds
+        .get_data(0)
+        .map_err(|e| AppError::invalid_data(format!("Invalid DDS data: {e}")))?;
+
+    let encoding = dds
+        .get_dxgi_format()
+        .and_then(dds_encoding)
+        .ok_or_else(|| {
+            AppError::invalid_data(format!(
+                "Unsupported DDS format {:?}",
+                dds.get_dxgi_format()
+            ))
+        })?;
+
+    let pixels = match encoding {
+        DdsEncoding::Rgba | DdsEncoding::Bgra => {
+            let size = (width * height * 4) as usize;
+            let mut pixels = data
+                .get(..size)
+                .ok_or_else(|| AppError::invalid_data("DDS data is shorter than its size"))?
+                .to_vec();
+            if encoding == DdsEncoding::Bgra {
+                pixels
+                    .chunks_exact_mut(4)
+                    .for_each(|pixel| pixel.swap(0, 2));
+            }
+            pixels
+        }
+        _ => {
+            let decode = match encoding {
+                DdsEncoding::Bc1 => texture2ddecoder::decode_bc1,
+                DdsEncoding::Bc3 => texture2ddecoder::decode_bc3,
+                _ => texture2ddecoder::decode_bc7,
+            };
+
+            let mut decoded = vec![0u32; (width * height) as usize];
+            decode(data, width as usize, height as usize, &mut decoded)
+                .map_err(|e| AppError::invalid_data(format!("Failed to decode DDS: {e}")))?;
+
+            // The decoders pack each pixel as little-endian BGRA
+            decoded
+                .into_iter()
+                .flat_map(|pixel| {
+                    let [b, g, r, a] = pixel.to_le_bytes();
+                    [r, g, b, a]
+                })
+                .collect()
+        }
+    };
+
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| AppError::invalid_data("Invalid DDS dimensions"))
+}
+
+fn dds_encoding(format: DxgiFormat) -> Option<DdsEncoding> {
+    match format {
+        DxgiFormat::BC1_Typeless | DxgiFormat::BC1_UNorm | DxgiFormat::BC1_UNorm_sRGB => {
+            Some(DdsEncoding::Bc1)
+        }
+        DxgiFormat::BC3_Typeless | DxgiFormat::BC3_UNorm | DxgiFormat::BC3_UNorm_sRGB => {
+            Some(DdsEncoding::Bc3)
+        }
+        DxgiFormat::BC7_Typeless | DxgiFormat::BC7_UNorm | DxgiFormat::BC7_UNorm_sRGB => {
+            Some(DdsEncoding::Bc7)
+        }
+        DxgiFormat::R8G8B8A8_Typeless
+        | DxgiFormat::R8G8B8A8_UNorm
+        | DxgiFormat::R8G8B8A8_UNorm_sRGB => Some(DdsEncoding::Rgba),
+        DxgiFormat::B8G8R8A8_Typeless
+        | DxgiFormat::B8G8R8A8_UNorm
+        | DxgiFormat::B8G8R8A8_UNorm_sRGB => Some(DdsEncoding::Bgra),
+        _ => None,
+    }
+}
+
+/// The `.dds` texture most likely to show the mod's base color.
+pub fn diffuse_texture(mod_dir: &Path, summary: &IniSummary) -> Option<PathBuf> {
+    summary
+        .resources
+        .iter()
+        .filter(|resource| resource.path.to_ascii_lowercase().ends_with(".dds"))
+        .filter_map(|resource| {
+            let name = resource.path.to_ascii_lowercase();
+            if NON_DIFFUSE_HINTS.iter().any(|hint| name.contains(hint)) {
+                return None;
+            }
+
+            let rank = DIFFUSE_HINTS
+                .iter()
+                .position(|hint| name.contains(hint))
+                .unwrap_or(DIFFUSE_HINTS.len());
+            Some((rank, mod_dir.join(&resource.path)))
+        })
+        .filter(|(_, path)| path.is_file())
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, path)| path)
+}
+
+/// Thumbnail decoded from the mod's diffuse texture, cached in the state folder and
+/// regenerated when the texture changes. `None` when the mod has no usable texture.
+pub fn fallback_thumbnail(
+    root: &Path,
+    mod_id: &str,
+    mod_dir: &Path,
+    summary: &IniSummary,
+) -> Option<PathBuf> {
+    let texture = diffuse_texture(mod_dir, summary)?;
+
+    cached_image(
+        root,
+        &format!("{}-texture.png", mod_id),
+        &texture,
+        |mut image| {
+            // Diffuse alpha is usually a shading mask rather than transparency
+            image.pixels_mut().for_each(|pixel| pixel[3] = u8::MAX);
+            downscale(image, FALLBACK_THUMBNAIL_SIZE)
+        },
+    )
+}
+
+/// Grid-sized copy of a mod thumbnail, so the library grid never loads full images.
+pub fn grid_thumbnail(root: &Path, mod_id: &str, thumbnail: &Path) -> Option<PathBuf> {
+    resized_copy(
+        root,
+        &format!("{}-{}.png", mod_id, GRID_THUMBNAIL_SIZE),
+        thumbnail,
+        GRID_THUMBNAIL_SIZE,
+    )
+}
+
+/// Copy of `source` downscaled to `size`, kept in the thumbnail cache as `name`.
+pub fn resized_copy(root: &Path, name: &str, source: &Path, size: u32) -> Option<PathBuf> {
+    cached_image(root, name, source, |image| downscale(image, size))
+}
+
+// Renders `source` into the thumbnail cache unless the cached copy is newer than it
+fn cached_image(
+    root: &Path,
+    name: &str,
+    source: &Path,
+    render: impl FnOnce(RgbaImage) -> RgbaImage,
+) -> Option<PathBuf> {
+    let cache_path = state_file(root, THUMBNAIL_DIR_NAME).ok()?.join(name);
+
+    let source_modified = fs::metadata(source).map(|m| modified_millis(&m)).ok()?;
+    let cache_modified = fs::metadata(&cache_path).map(|m| modified_millis(&m));
+    if cache_modified.is_ok_and(|cache_modified| cache_modified >= source_modified) {
+        return Some(cache_path);
+    }
+
+    let result = decode_texture(source).and_then(|image| {
+        fs::create_dir_all(cache_path.parent().unwrap_or(root))
+            .map_err(|e| AppError::io("Failed to create thumbnail cache", e))?;
+        fs::write(&cache_path, encode_png(&render(image))?)
+            .map_err(|e| AppError::io("Failed to write thumbnail", e))
+    });
+
+    match result {
+        Ok(()) => Some(cache_path),
+        Err(error) => {
+            eprintln!(
+                "[texture] No cached thumbnail from {}: {}",
+                source.display(),
+                error
+            );
+            None
+        }
+    }
+}
+
+/// Shrinks `image` to fit `max_size`, keeping its aspect ratio. Smaller images are kept.
+pub fn downscale(image: RgbaImage, max_size: u32) -> RgbaImage {
+    if image.width() <= max_size && image.height() <= max_size {
+        return image;
+    }
+
+    DynamicImage::ImageRgba8(image)
+        .resize(max_size, max_size, FilterType::Triangle)
+        .to_rgba8()
+}
+
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, AppError> {
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, ImageFormat::Png)
+        .map_err(|e| AppError::invalid_data(format!("Failed to encode PNG: {e}")))?;
+    Ok(bytes.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddsfile::{AlphaMode, D3D10ResourceDimension, NewDxgiParams};
+
+    #[test]
+    fn decodes_uncompressed_bgra_dds() {
+        let mut dds = Dds::new_dxgi(NewDxgiParams {
+            height: 1,
+            width: 2,
+            depth: None,
+            format: DxgiFormat::B8G8R8A8_UNorm,
+            mipmap_levels: None,
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: D3D10ResourceDimension::Texture2D,
+            alpha_mode: AlphaMode::Straight,
+        })
+        .unwrap();
+        dds.get_mut_data(0)
+            .unwrap()
+            .copy_from_slice(&[255, 0, 0, 255, 0, 0, 255, 128]);
+
+        let mut bytes = Vec::new();
+        dds.write(&mut bytes).unwrap();
+        let image = decode_dds(&bytes).unwrap();
+
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [255, 0, 0, 128]);
+    }
+
+    #[test]
+    fn rejects_dds_sizes_direct3d_cannot_load() {
+        let dds = Dds::new_dxgi(NewDxgiParams {
+            height: 1,
+            width: MAX_DDS_SIZE + 1,
+            depth: None,
+            format: DxgiFormat::B8G8R8A8_UNorm,
+            mipmap_levels: None,
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: D3D10ResourceDimension::Texture2D,
+            alpha_mode: AlphaMode::Straight,
+        })
+        .unwrap();
+
+        let mut bytes = Vec::new();
+        dds.write(&mut bytes).unwrap();
+
+        assert_eq!(
+            decode_dds(&bytes).unwrap_err().code,
+            crate::services::error::ErrorCode::InvalidData
+        );
+    }
+
+    #[test]
+    fn caches_grid_sized_thumbnails() {
+        let root = std::env::temp_dir().join(format!("wuwa-texture-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let thumbnail = root.join("thumbnail.png");
+        fs::write(&thumbnail, encode_png(&RgbaImage::new(600, 300)).unwrap()).unwrap();
+
+        let cached = grid_thumbnail(&root, "mod-id", &thumbnail).unwrap();
+        let image = image::open(&cached).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(cached.ends_with("thumbnails/mod-id-256.png"));
+        assert_eq!((image.width(), image.height()), (256, 128));
+    }
+}
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::services::enable_state::parse_name;
+use crate::services::error::AppError;
+use crate::services::journal::{move_path, record, FsChange};
+use crate::services::mod_json::now_millis;
+use crate::services::settings::load_settings;
+use crate::services::storage::{state_dir, write_atomic};
+
+// Each deleted mod goes to `<id>/<folder name>` in here, next to an `<id>/trash.json` with
+// where it came from. The DISABLED prefix keeps 3DMigoto from loading trashed mods.
+const TRASH_DIR_NAME: &str = "DISABLED_trash";
+const ENTRY_FILE_NAME: &str = "trash.json";
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+const MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    // Display name, without the disabled prefix
+    pub name: String,
+    pub original_path: String,
+    // Unix millis
+    pub deleted_at: u64,
+    // Bytes
+    pub size: u64,
+}
+
+/// Trashed mods, most recently deleted first. Applies the auto-purge settings first.
+#[tauri::command]
+pub fn list_trash(path: String) -> Result<Vec<TrashEntry>, AppError> {
+    let root = Path::new(&path);
+
+    if !root.exists() || !root.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    auto_purge(root);
+
+    Ok(read_entries(root)
+        .into_iter()
+        .filter(|entry| trashed_path(root, entry).exists())
+        .collect())
+}
+
+/// Moves a trashed mod back to where it was deleted from and returns that path.
+#[tauri::command]
+pub fn restore_from_trash(path: String, id: String) -> Result<String, AppError> {
+    let root = Path::new(&path);
+    let entry = read_entries(root)
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Trash entry {} does not exist", id)))?;
+
+    let from = trashed_path(root, &entry);
+    let to = PathBuf::from(&entry.original_path);
+
+    if !from.exists() {
+        return Err(AppError::not_found(format!(
+            "{} is no longer in the trash",
+            entry.name
+        )));
+    }
+    if to.exists() {
+        return Err(
+            AppError::already_exists(format!("{} already exists", to.display())).with_path(&to),
+        );
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::io(format!("Failed to create {}", parent.display()), e))?;
+    }
+
+    // trash.json stays behind so undoing the restore puts the mod back in the trash
+    move_path(&from, &to)?;
+    println!("[trash] Restored '{}' to {}", entry.name, to.display());
+    record(
+        root,
+        &format!("Restore {}", entry.name),
+        vec![FsChange::Move {
+            from,
+            to: to.clone(),
+        }],
+    );
+
+    Ok(to.to_string_lossy().to_string())
+}
+
+/// Permanently deletes the given trash entries, or the whole trash without `ids`. Returns how
+/// many trashed mods were purged.
+#[tauri::command]
+pub fn purge_trash(path: String, ids: Option<Vec<String>>) -> Result<usize, AppError> {
+    let root = Path::new(&path);
+    let mut purged = 0;
+
+    for entry in read_entries(root) {
+        if ids.as_ref().is_some_and(|ids| !ids.contains(&entry.id)) {
+            continue;
+        }
+        if trashed_path(root, &entry).exists() {
+            purged += 1;
+        }
+        purge(root, &entry)?;
+    }
+
+    println!("[trash] Purged {} entries", purged);
+    Ok(purged)
+}
+
+/// Moves a mod folder into the trash. The returned change is what the journal needs to undo it.
+pub fn trash_mod(root: &Path, mod_dir: &Path) -> Result<FsChange, AppError> {
+    let folder_name = mod_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| AppError::invalid_input("Invalid mod directory name").with_path(mod_dir))?;
+
+    let entry = TrashEntry {
+        id: Uuid::new_v4().to_string(),
+        name: parse_name(folder_name).1.to_string(),
+        original_path: mod_dir.to_string_lossy().to_string(),
+        deleted_at: now_millis(),
+        size: dir_size(mod_dir),
+    };
+
+    let entry_dir = entry_dir(root, &entry.id);
+    fs::create_dir_all(&entry_dir)
+        .map_err(|e| AppError::io(format!("Failed to create {}", entry_dir.display()), e))?;
+
+    let target = trashed_path(root, &entry);
+    if let Err(error) = move_path(mod_dir, &target) {
+        let _ = fs::remove_dir_all(&entry_dir);
+        return Err(error);
+    }
+
+    let json = serde_json::to_string_pretty(&entry)?;
+    write_atomic(&entry_dir.join(ENTRY_FILE_NAME), json)?;
+    println!("[trash] Moved '{}' to the trash", entry.name);
+
+    auto_purge(root);
+
+    Ok(FsChange::Move {
+        from: mod_dir.to_path_buf(),
+        to: target,
+    })
+}
+
+// Drops entries past the configured age, then the oldest ones until the trash fits the
+// configured size. The most recent deletion is never purged for size alone.
+fn auto_purge(root: &Path) {
+    let settings = load_settings(root);
+    let now = now_millis();
+    let max_age = settings.trash_max_age_days as u64 * DAY_MILLIS;
+    let max_size = settings.trash_max_size_mb * MB;
+
+    let mut total = 0;
+    let mut newer = 0;
+    for entry in read_entries(root) {
+        // Entries whose mod was restored only keep their trash.json, for undo
+        let in_trash = trashed_path(root, &entry).exists();
+        if in_trash {
+            total += entry.size;
+        }
+
+        let expired = max_age > 0 && now.saturating_sub(entry.deleted_at) > max_age;
+        let over_size = in_trash && max_size > 0 && newer > 0 && total > max_size;
+        if in_trash {
+            newer += 1;
+        }
+        if expired || over_size {
+            println!("[trash] Auto-purging '{}'", entry.name);
+            if let Err(error) = purge(root, &entry) {
+                eprintln!("[trash] {}", error);
+            }
+        }
+    }
+}
+
+fn purge(root: &Path, entry: &TrashEntry) -> Result<(), AppError> {
+    let dir = entry_dir(root, &entry.id);
+    fs::remove_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Failed to purge '{}'", entry.name), e))
+}
+
+// Every entry with a readable trash.json, most recently deleted first
+fn read_entries(root: &Path) -> Vec<TrashEntry> {
+    let Ok(dirs) = fs::read_dir(state_dir(root).join(TRASH_DIR_NAME)) else {
+        return Vec::new();
+    };
+
+    let mut entries = dirs
+        .flatten()
+        .filter_map(|dir| fs::read_to_string(dir.path().join(ENTRY_FILE_NAME)).ok())
+        .filter_map(|content| serde_json::from_str::<TrashEntry>(&content).ok())
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    entries
+}
+
+fn entry_dir(root: &Path, id: &str) -> PathBuf {
+    state_dir(root).join(TRASH_DIR_NAME).join(id)
+}
+
+fn trashed_path(root: &Path, entry: &TrashEntry) -> PathBuf {
+    let folder_name = Path::new(&entry.original_path)
+        .file_name()
+        .unwrap_or_default();
+    entry_dir(root, &entry.id).join(folder_name)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::settings::save_settings;
+
+    #[test]
+    fn trashes_restores_and_purges_by_size() {
+        let root = std::env::temp_dir().join(format!("wuwa-trash-{}", uuid::Uuid::new_v4()));
+        let root_path = root.to_string_lossy().to_string();
+        for name in ["DISABLED_Alpha", "Beta"] {
+            fs::create_dir_all(root.join(name)).unwrap();
+            fs::write(root.join(name).join("mod.ini"), vec![b';'; 1024 * 1024]).unwrap();
+        }
+
+        trash_mod(&root, &root.join("DISABLED_Alpha")).unwrap();
+        let trashed = list_trash(root_path.clone()).unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].name, "Alpha");
+        assert!(!root.join("DISABLED_Alpha").exists());
+
+        let restored = restore_from_trash(root_path.clone(), trashed[0].id.clone()).unwrap();
+        assert!(Path::new(&restored).join("mod.ini").is_file());
+        assert!(list_trash(root_path.clone()).unwrap().is_empty());
+
+        let mut settings = load_settings(&root);
+        settings.trash_max_size_mb = 1;
+        save_settings(&root, &settings).unwrap();
+        trash_mod(&root, &root.join("DISABLED_Alpha")).unwrap();
+        // Entries are ordered by deletion time
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        trash_mod(&root, &root.join("Beta")).unwrap();
+        let kept = list_trash(root_path.clone()).unwrap();
+
+        assert_eq!(purge_trash(root_path.clone(), None).unwrap(), 1);
+        let emptied = list_trash(root_path).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            kept.iter()
+                .map(|entry| entry.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Beta"]
+        );
+        assert!(emptied.is_empty());
+    }
+}
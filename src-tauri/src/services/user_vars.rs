@@ -0,0 +1,304 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::error::AppError;
+use crate::services::journal::{read_text, record, FsChange};
+use crate::services::mod_service::Mod;
+use crate::services::storage::replace_atomic;
+
+// 3DMigoto keeps persisted `global persist` variables next to d3dx.ini, one level above Mods.
+const USER_INI_NAME: &str = "d3dx_user.ini";
+
+/// One persisted variable, e.g. `$\mods\jinhsi\skin.ini\swapvar = 1`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedVar {
+    // Full key as written in d3dx_user.ini
+    pub name: String,
+    // Namespace part of the key, lower-cased
+    pub namespace: String,
+    // Variable name with its `$`, e.g. `$swapvar`
+    pub variable: String,
+    pub value: String,
+}
+
+#[tauri::command]
+pub fn get_mod_user_vars(path: String, mod_data: Mod) -> Result<Vec<PersistedVar>, AppError> {
+    let root = Path::new(&path);
+    Ok(vars_for_mod(root, &mod_data, &read_user_vars(root)?))
+}
+
+#[tauri::command]
+pub fn set_mod_user_var(path: String, name: String, value: String) -> Result<(), AppError> {
+    let root = Path::new(&path);
+
+    if parse_var_key(&name).is_none() {
+        return Err(AppError::invalid_input(format!(
+            "'{}' is not a persisted variable name",
+            name
+        )));
+    }
+    if value.trim().is_empty() || value.contains(['\n', '\r']) {
+        return Err(AppError::invalid_input(
+            "Variable value must be a single non-empty line",
+        ));
+    }
+
+    let label = format!("Set {} to {}", name, value.trim());
+    write_journaled(
+        root,
+        &label,
+        &HashMap::from([(name, Some(value.trim().to_string()))]),
+    )
+}
+
+/// Drops the mod's saved values so 3DMigoto falls back to the ini defaults on next load.
+#[tauri::command]
+pub fn reset_mod_user_vars(path: String, mod_data: Mod) -> Result<usize, AppError> {
+    let root = Path::new(&path);
+    let vars = vars_for_mod(root, &mod_data, &read_user_vars(root)?);
+
+    let updates = vars
+        .iter()
+        .map(|var| (var.name.clone(), None))
+        .collect::<HashMap<_, _>>();
+    write_journaled(
+        root,
+        &format!("Reset variables of {}", mod_data.name),
+        &updates,
+    )?;
+
+    Ok(vars.len())
+}
+
+// The journal keeps text, and a missing `before` means the file didn't exist. A user ini
+// that isn't UTF-8 is written without an entry rather than one that deletes it on undo.
+fn write_journaled(
+    root: &Path,
+    label: &str,
+    updates: &HashMap<String, Option<String>>,
+) -> Result<(), AppError> {
+    let user_ini = user_ini_path(root);
+    let before = read_text(&user_ini);
+    let unreadable = before.is_none() && user_ini.exists();
+
+    write_user_vars(root, updates)?;
+    if unreadable {
+        eprintln!("[user-vars] {USER_INI_NAME} is not UTF-8, '{label}' can't be undone");
+    } else {
+        record(root, label, vec![FsChange::write(&user_ini, before)]);
+    }
+
+    Ok(())
+}
+
+pub fn user_ini_path(root: &Path) -> PathBuf {
+    root.parent().unwrap_or(root).join(USER_INI_NAME)
+}
+
+/// All persisted variables in d3dx_user.ini, or none when the game never saved any.
+pub fn read_user_vars(root: &Path) -> Result<Vec<PersistedVar>, AppError> {
+    let user_ini = user_ini_path(root);
+
+    if !user_ini.exists() {
+        return Ok(Vec::new());
+    }
+
+    let bytes = fs::read(&user_ini)
+        .map_err(|e| AppError::io(format!("Failed to read {USER_INI_NAME}"), e))?;
+
+    Ok(String::from_utf8_lossy(&bytes)
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            let name = name.trim();
+            let (namespace, variable) = parse_var_key(name)?;
+
+            Some(PersistedVar {
+                name: name.to_string(),
+                namespace,
+                variable,
+                value: value.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Variables whose namespace is one of the mod's ini files or declared namespaces.
+pub fn vars_for_mod(root: &Path, mod_data: &Mod, vars: &[PersistedVar]) -> Vec<PersistedVar> {
+    let namespaces = mod_namespaces(root, mod_data);
+
+    vars.iter()
+        .filter(|var| namespaces.contains(&var.namespace))
+        .cloned()
+        .collect()
+}
+
+// 3DMigoto names a file's namespace after its path relative to the d3dx.ini folder, unless
+// the ini declares `namespace = ...`. The enabled folder name is used so values saved
+// while the mod was active still match after it gets disabled.
+fn mod_namespaces(root: &Path, mod_data: &Mod) -> Vec<String> {
+    let mods_folder = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut folder = vec![mods_folder];
+    folder.extend(
+        mod_data
+            .group
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .map(str::to_string),
+    );
+    folder.push(mod_data.name.clone());
+    let folder = folder.join("\\");
+
+    mod_data
+        .ini
+        .files
+        .iter()
+        .map(|file| format!("{}\\{}", folder, file.replace('/', "\\")))
+        .chain(mod_data.ini.namespaces.iter().cloned())
+        .map(|namespace| namespace.to_lowercase())
+        .collect()
+}
+
+// `$\mods\foo\bar.ini\swapvar` -> (`mods\foo\bar.ini`, `$swapvar`)
+fn parse_var_key(name: &str) -> Option<(String, String)> {
+    let key = name.strip_prefix("$\\")?;
+    let (namespace, variable) = key.rsplit_once('\\')?;
+
+    if namespace.is_empty() || variable.is_empty() {
+        return None;
+    }
+
+    Some((namespace.to_lowercase(), format!("${}", variable)))
+}
+
+/// Sets (`Some`) or removes (`None`) variables, keeping every other line of the file.
+pub fn write_user_vars(
+    root: &Path,
+    updates: &HashMap<String, Option<String>>,
+) -> Result<(), AppError> {
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    let user_ini = user_ini_path(root);
+    let content = fs::read(&user_ini).unwrap_or_default();
+
+    let lookup = updates
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), (name, value)))
+        .collect::<HashMap<_, _>>();
+    let mut written = Vec::new();
+    // Lines stay raw bytes, the file is the game's and need not be UTF-8
+    let mut lines = Vec::new();
+
+    for line in content.split_inclusive(|&byte| byte == b'\n') {
+        let key = String::from_utf8_lossy(line)
+            .split_once('=')
+            .map(|(name, _)| name.trim().to_lowercase());
+
+        match key.as_ref().and_then(|key| lookup.get(key)) {
+            Some((name, Some(value))) => {
+                let ending = line.len() - line.trim_ascii_end().len();
+                let mut replaced = format!("{} = {}", name, value).into_bytes();
+                replaced.extend_from_slice(&line[line.len() - ending..]);
+                lines.push(replaced);
+                written.push(name.to_lowercase());
+            }
+            Some((_, None)) => {}
+            None => lines.push(line.to_vec()),
+        }
+    }
+
+    let mut missing = updates
+        .iter()
+        .filter(|(name, value)| value.is_some() && !written.contains(&name.to_lowercase()))
+        .collect::<Vec<_>>();
+    missing.sort();
+
+    if !missing.is_empty() {
+        // New values go at the end of [Constants], which is created when missing
+        let is_header = |line: &[u8], name: Option<&[u8]>| {
+            let trimmed = line.trim_ascii();
+            trimmed.starts_with(b"[") && name.is_none_or(|name| trimmed.eq_ignore_ascii_case(name))
+        };
+        let header = lines
+            .iter()
+            .position(|line| is_header(line, Some(b"[Constants]")));
+        let section_end = header.and_then(|header| {
+            lines[header + 1..]
+                .iter()
+                .position(|line| is_header(line, None))
+                .map(|offset| header + 1 + offset)
+        });
+        let insert_at = match section_end {
+            Some(section_end) => section_end,
+            None => {
+                // Appending, so the last line needs its line break
+                if let Some(last) = lines.last_mut().filter(|last| !last.ends_with(b"\n")) {
+                    last.extend_from_slice(b"\r\n");
+                }
+                if header.is_none() {
+                    lines.push(b"[Constants]\r\n".to_vec());
+                }
+                lines.len()
+            }
+        };
+
+        let new_lines = missing.into_iter().map(|(name, value)| {
+            format!("{} = {}\r\n", name, value.as_deref().unwrap_or_default()).into_bytes()
+        });
+        lines.splice(insert_at..insert_at, new_lines);
+    }
+
+    replace_atomic(&user_ini, lines.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_variable_keys_into_namespace_and_name() {
+        assert_eq!(
+            parse_var_key("$\\Mods\\Jinhsi\\Skin.ini\\swapvar"),
+            Some(("mods\\jinhsi\\skin.ini".to_string(), "$swapvar".to_string()))
+        );
+        assert_eq!(parse_var_key("$swapvar"), None);
+    }
+
+    #[test]
+    fn rewrites_only_the_updated_lines_of_the_user_ini() {
+        let dir = std::env::temp_dir().join(format!("wuwa-user-vars-{}", uuid::Uuid::new_v4()));
+        let root = dir.join("Mods");
+        fs::create_dir_all(&root).unwrap();
+        // A CP1252 comment and LF endings, as another tool may have left them
+        let content: &[u8] = b"; Caf\xe9\n[Constants]\n$\\mods\\a.ini\\swap = 1\n\
+                               $\\mods\\a.ini\\gone = 2\n\n[Present]\nrun = x";
+        fs::write(user_ini_path(&root), content).unwrap();
+
+        write_user_vars(
+            &root,
+            &HashMap::from([
+                ("$\\mods\\a.ini\\swap".to_string(), Some("3".to_string())),
+                ("$\\mods\\a.ini\\gone".to_string(), None),
+                ("$\\mods\\b.ini\\new".to_string(), Some("4".to_string())),
+            ]),
+        )
+        .unwrap();
+        let rewritten = fs::read(user_ini_path(&root)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            rewritten,
+            b"; Caf\xe9\n[Constants]\n$\\mods\\a.ini\\swap = 3\n\n\
+              $\\mods\\b.ini\\new = 4\r\n[Present]\nrun = x"
+        );
+    }
+}
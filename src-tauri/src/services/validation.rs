@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::services::error::AppError;
+use crate::services::index::load_library;
+use crate::services::ini::{read_ini, summarize_mod, IniSummary};
+use crate::services::mod_json::{corrupt_backups, is_corrupt_backup};
+use crate::services::mod_service::Mod;
+
+// Files the manager writes itself, a folder holding only these has nothing to load
+const MANAGER_FILES: [&str; 3] = ["mod.json", "mod.json.bak", "thumbnail.png"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticKind {
+    EmptyFolder,
+    // Files are present but none of them is an ini
+    MissingIni,
+    MissingResource,
+    IniSyntax,
+    UnreadableIni,
+    // mod.json could not be parsed and was moved aside
+    CorruptModJson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+    pub message: String,
+    // Ini file relative to the mod folder, when the problem comes from one
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModDiagnostics {
+    pub mod_id: String,
+    pub mod_name: String,
+    pub mod_path: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[tauri::command]
+pub fn validate_mod(mod_data: Mod) -> Result<ModDiagnostics, AppError> {
+    let mod_dir = Path::new(&mod_data.path);
+
+    if !mod_dir.exists() || !mod_dir.is_dir() {
+        return Err(AppError::not_found("Mod directory does not exist"));
+    }
+
+    // Re-read the inis, the grid's copy may predate the last edit
+    let summary = summarize_mod(mod_dir);
+    Ok(diagnose(&mod_data, &summary))
+}
+
+/// Diagnostics for every mod in the library that has at least one problem.
+#[tauri::command]
+pub async fn validate_library(path: String) -> Result<Vec<ModDiagnostics>, AppError> {
+    let dir = Path::new(&path);
+
+    if !dir.exists() || !dir.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    let mods = load_library(dir).await?;
+
+    Ok(mods
+        .iter()
+        .map(|mod_data| diagnose(mod_data, &mod_data.ini))
+        .filter(|result| !result.diagnostics.is_empty())
+        .collect())
+}
+
+fn diagnose(mod_data: &Mod, summary: &IniSummary) -> ModDiagnostics {
+    let mod_dir = Path::new(&mod_data.path);
+    let mut diagnostics = Vec::new();
+
+    if !has_content(mod_dir) {
+        diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::EmptyFolder,
+            severity: Severity::Error,
+            message: "Mod folder is empty".to_string(),
+            file: None,
+            line: None,
+            section: None,
+        });
+    } else if mod_data.ini_files.is_empty() && summary.files.is_empty() {
+        diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::MissingIni,
+            severity: Severity::Warning,
+            message: "Mod folder has no ini file, 3DMigoto will not load it".to_string(),
+            file: None,
+            line: None,
+            section: None,
+        });
+    }
+
+    for file in &summary.files {
+        diagnostics.extend(ini_diagnostics(mod_dir, file));
+    }
+
+    for backup in corrupt_backups(mod_dir) {
+        diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::CorruptModJson,
+            severity: Severity::Warning,
+            message: format!(
+                "mod.json could not be read, its contents were saved as {}",
+                backup
+            ),
+            file: Some(backup),
+            line: None,
+            section: None,
+        });
+    }
+
+    for resource in &summary.resources {
+        if !mod_dir.join(&resource.path).is_file() {
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::MissingResource,
+                severity: Severity::Error,
+                message: format!("Referenced file {} does not exist", resource.path),
+                file: Some(resource.file.clone()),
+                line: None,
+                section: Some(resource.section.clone()),
+            });
+        }
+    }
+
+    ModDiagnostics {
+        mod_id: mod_data.id.clone(),
+        mod_name: mod_data.name.clone(),
+        mod_path: mod_data.path.clone(),
+        diagnostics,
+    }
+}
+
+fn ini_diagnostics(mod_dir: &Path, file: &str) -> Vec<Diagnostic> {
+    match read_ini(&mod_dir.join(file)) {
+        Ok(document) => document
+            .errors
+            .into_iter()
+            .map(|error| Diagnostic {
+                kind: DiagnosticKind::IniSyntax,
+                severity: Severity::Error,
+                message: error.message,
+                file: Some(file.to_string()),
+                line: Some(error.line),
+                section: None,
+            })
+            .collect(),
+        Err(error) => vec![Diagnostic {
+            kind: DiagnosticKind::UnreadableIni,
+            severity: Severity::Error,
+            message: error.message,
+            file: Some(file.to_string()),
+            line: None,
+            section: None,
+        }],
+    }
+}
+
+// Whether the folder holds anything besides files the manager wrote
+fn has_content(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            has_content(&path)
+        } else {
+            let name = entry.file_name().to_string_lossy().to_string();
+            !MANAGER_FILES.contains(&name.as_str()) && !is_corrupt_backup(&name)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ini::list_ini_files;
+
+    fn mod_at(dir: &Path) -> Mod {
+        Mod {
+            id: "test".to_string(),
+            author: String::new(),
+            description: String::new(),
+            version: String::new(),
+            category: String::new(),
+            url: String::new(),
+            name: "Test".to_string(),
+            path: dir.to_string_lossy().to_string(),
+            group: String::new(),
+            thumbnail: String::new(),
+            grid_thumbnail: String::new(),
+            enabled: true,
+            ini: summarize_mod(dir),
+            ini_files: list_ini_files(dir),
+            user_vars: Vec::new(),
+            gallery: Vec::new(),
+            tags: Vec::new(),
+            favorite: false,
+            rating: None,
+            notes: String::new(),
+            installed_at: None,
+            last_enabled_at: None,
+        }
+    }
+
+    #[test]
+    fn reports_missing_resources_syntax_errors_and_empty_folders() {
+        let root = std::env::temp_dir().join(format!("wuwa-validate-{}", uuid::Uuid::new_v4()));
+        let broken = root.join("Broken");
+        let empty = root.join("Empty");
+        fs::create_dir_all(broken.join("Meshes")).unwrap();
+        fs::create_dir_all(&empty).unwrap();
+        fs::write(broken.join("Meshes").join("Body.ib"), b"").unwrap();
+        fs::write(empty.join("mod.json"), "{}").unwrap();
+        fs::write(
+            broken.join("Skin.ini"),
+            "[TextureOverrideBody]\nif $a == 1\n\n[ResourceBodyIB]\nfilename = Meshes\\Body.ib\n\n\
+             [ResourceBodyVB]\nfilename = Meshes\\Body.buf\n",
+        )
+        .unwrap();
+
+        let broken_result = diagnose(&mod_at(&broken), &summarize_mod(&broken));
+        let empty_result = diagnose(&mod_at(&empty), &summarize_mod(&empty));
+        fs::remove_dir_all(&root).unwrap();
+
+        let kinds = broken_result
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiagnosticKind::IniSyntax, Some(2)),
+                (DiagnosticKind::MissingResource, None),
+            ]
+        );
+        assert_eq!(
+            broken_result.diagnostics[1].section.as_deref(),
+            Some("ResourceBodyVB")
+        );
+        assert_eq!(
+            empty_result.diagnostics[0].kind,
+            DiagnosticKind::EmptyFolder
+        );
+    }
+}
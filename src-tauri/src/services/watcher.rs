@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::services::error::{AppError, ErrorCode};
+use crate::services::index::{library_loaded, load_library};
+use crate::services::mod_service::Mod;
+use crate::services::storage::state_dir;
+
+// Extracting an archive or copying a mod produces hundreds of events, wait for it to settle.
+const DEBOUNCE_MS: u64 = 750;
+
+/// Holds the active watcher, registered with `.manage()` so commands can swap it.
+#[derive(Default)]
+pub struct LibraryWatcher {
+    active: Mutex<Option<ActiveWatcher>>,
+}
+
+struct ActiveWatcher {
+    root: PathBuf,
+    // Dropping the debouncer stops the watch
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+#[derive(Debug, Default)]
+struct LibraryChanges {
+    added: Vec<Mod>,
+    removed: Vec<Mod>,
+    changed: Vec<Mod>,
+}
+
+#[tauri::command]
+pub async fn watch_mods_folder(
+    path: String,
+    app: AppHandle,
+    watcher: State<'_, LibraryWatcher>,
+) -> Result<(), AppError> {
+    let root = PathBuf::from(&path);
+
+    if !root.exists() || !root.is_dir() {
+        return Err(AppError::not_found("Invalid directory path"));
+    }
+
+    if let Some(active) = watcher.active.lock().unwrap().as_ref() {
+        if active.root == root {
+            return Ok(());
+        }
+    }
+
+    let mods = load_library(&root).await?;
+    library_loaded(&app, &root, &mods);
+    let mut known = by_id(mods);
+    let watched_root = root.clone();
+
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(DEBOUNCE_MS),
+        move |result: DebounceEventResult| {
+            let events = match result {
+                Ok(events) => events,
+                Err(error) => {
+                    eprintln!("[watcher] Watch error: {}", error);
+                    return;
+                }
+            };
+
+            // Our own index and metadata writes land in the state folder, ignore them.
+            let manager_dir = state_dir(&watched_root);
+            if events.iter().all(|event| event.path.starts_with(&manager_dir)) {
+                return;
+            }
+
+            let mods = match tauri::async_runtime::block_on(load_library(&watched_root)) {
+                Ok(mods) => {
+                    library_loaded(&app, &watched_root, &mods);
+                    by_id(mods)
+                }
+                Err(error) => {
+                    eprintln!("[watcher] Failed to rescan library: {}", error);
+                    return;
+                }
+            };
+
+            let changes = diff_library(&known, &mods);
+            known = mods;
+            emit_changes(&app, changes);
+        },
+    )
+    .map_err(|e| AppError::new(ErrorCode::Io, format!("Failed to create watcher: {e}")))?;
+
+    debouncer
+        .watcher()
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| {
+            AppError::new(
+                ErrorCode::Io,
+                format!("Failed to watch {}: {e}", root.display()),
+            )
+            .with_path(&root)
+        })?;
+
+    println!("[watcher] Watching {}", root.display());
+
+    *watcher.active.lock().unwrap() = Some(ActiveWatcher {
+        root,
+        _debouncer: debouncer,
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unwatch_mods_folder(watcher: State<'_, LibraryWatcher>) {
+    if let Some(active) = watcher.active.lock().unwrap().take() {
+        println!("[watcher] Stopped watching {}", active.root.display());
+    }
+}
+
+fn by_id(mods: Vec<Mod>) -> HashMap<String, Mod> {
+    mods.into_iter()
+        .map(|mod_data| (mod_data.id.clone(), mod_data))
+        .collect()
+}
+
+// Mods are matched by id, so renaming a folder (enable/disable) is reported as a change.
+fn diff_library(before: &HashMap<String, Mod>, after: &HashMap<String, Mod>) -> LibraryChanges {
+    let mut changes = LibraryChanges::default();
+
+    for (id, mod_data) in after {
+        match before.get(id) {
+            None => changes.added.push(mod_data.clone()),
+            Some(previous) if previous != mod_data => changes.changed.push(mod_data.clone()),
+            Some(_) => {}
+        }
+    }
+
+    for (id, mod_data) in before {
+        if !after.contains_key(id) {
+            changes.removed.push(mod_data.clone());
+        }
+    }
+
+    changes
+}
+
+fn emit_changes(app: &AppHandle, changes: LibraryChanges) {
+    for (event, mods) in [
+        ("mod-added", changes.added),
+        ("mod-removed", changes.removed),
+        ("mod-changed", changes.changed),
+    ] {
+        if mods.is_empty() {
+            continue;
+        }
+
+        println!("[watcher] {} x{}", event, mods.len());
+        if let Err(error) = app.emit(event, mods) {
+            eprintln!("[watcher] Failed to emit {}: {}", event, error);
+        }
+    }
+}
@@ -8,7 +8,7 @@ import { Character } from "../interfaces/Character.interface";
 import { COLORS, STYLE } from "../constants/styling.constant";
 import StatusButton from "./StatusButton";
 import { motion } from "framer-motion";
-import { getImageSrc } from "../services/image.service";
+import { getImageSrc, getModImageUrl } from "../services/image.service";
 
 interface ModInfoPanelProps {
   mod: Mod;
@@ -38,16 +38,9 @@ const ModInfoPanel: React.FC<ModInfoPanelProps> = ({
 
   useEffect(() => {
     setFocusedMod(mod);
-    if (mod.thumbnail) {
-      getImageSrc(mod.thumbnail)
-        .then(setDisplayThumbnail)
-        .catch((error) => {
-          console.error("Error loading thumbnail preview:", error);
-          setDisplayThumbnail("");
-        });
-    } else {
-      setDisplayThumbnail("");
-    }
+    setDisplayThumbnail(
+      mod.thumbnail ? getModImageUrl(mod.id, "thumbnail", 512) : ""
+    );
     if (isOpen && thumbnailRefZone.current) {
       thumbnailRefZone.current.focus();
     }
@@ -0,0 +1,23 @@
+export type ErrorCode =
+  | "notFound"
+  | "alreadyExists"
+  | "permissionDenied"
+  | "invalidData"
+  | "invalidInput"
+  | "diskFull"
+  | "toolMissing"
+  | "network"
+  | "io"
+  | "other";
+
+// Rejection value of every command
+export interface AppError {
+  code: ErrorCode;
+  message: string;
+  context: {
+    path: string | null;
+    modId: string | null;
+    // HTTP status of a failed download
+    status: number | null;
+  };
+}
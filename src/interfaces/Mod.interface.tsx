@@ -1,12 +1,93 @@
+export interface OverrideHash {
+    hash: string;
+    section: string;
+    file: string;
+    matchFirstIndex: string | null;
+}
+
+export interface KeyCombination {
+    key: string;
+    modifiers: string[];
+    excluded: string[];
+}
+
+export interface KeyBinding {
+    section: string;
+    file: string;
+    key: string;
+    combination: KeyCombination;
+    kind: "activate" | "hold" | "toggle" | "cycle" | string;
+    variables: string[];
+}
+
+export interface ResourceRef {
+    section: string;
+    file: string;
+    path: string;
+}
+
+export interface IniSummary {
+    files: string[];
+    namespaces: string[];
+    hashes: OverrideHash[];
+    keybinds: KeyBinding[];
+    resources: ResourceRef[];
+    variables: string[];
+    errors: string[];
+}
+
+export interface IniFileState {
+    file: string;
+    name: string;
+    enabled: boolean;
+}
+
+export interface PersistedVar {
+    name: string;
+    namespace: string;
+    variable: string;
+    value: string;
+}
+
+export interface ConflictingMod {
+    id: string;
+    name: string;
+    path: string;
+    category: string;
+    section: string;
+    file: string;
+}
+
+export interface HashConflict {
+    hash: string;
+    matchFirstIndex: string | null;
+    characters: string[];
+    mods: ConflictingMod[];
+}
+
 export interface Mod {
     id: string;
     URL: string;
     name: string;
     path: string;
+    group: string;
     author: string;
     description: string;
     category: string;
     version: string;
     thumbnail: string;
+    gridThumbnail: string;
     enabled: boolean;
-}
\ No newline at end of file
+    ini: IniSummary;
+    iniFiles: IniFileState[];
+    userVars: PersistedVar[];
+    gallery: string[];
+    tags: string[];
+    favorite: boolean;
+    // 1 to 5, null when unrated
+    rating: number | null;
+    notes: string;
+    // Unix millis
+    installedAt: number | null;
+    lastEnabledAt: number | null;
+}
@@ -0,0 +1,22 @@
+import { AppError, ErrorCode } from "../interfaces/AppError.interface";
+
+const RECOVERY_HINTS: Partial<Record<ErrorCode, string>> = {
+  alreadyExists: "Rename or remove the existing folder first.",
+  permissionDenied: "Close the game or any program using the mod folder and try again.",
+  diskFull: "Free up some disk space and try again.",
+  toolMissing: "Install 7-Zip and make sure 7z is on your PATH.",
+  network: "Check your connection and the download link.",
+};
+
+export const isAppError = (error: unknown): error is AppError =>
+  typeof error === "object" && error !== null && "code" in error && "message" in error;
+
+// The backend's message with a hint on how to fix it, or `fallback` for unexpected errors
+export const describeError = (error: unknown, fallback: string): string => {
+  if (!isAppError(error)) {
+    return fallback;
+  }
+
+  const hint = RECOVERY_HINTS[error.code];
+  return hint ? `${error.message}. ${hint}` : error.message;
+};
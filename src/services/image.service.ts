@@ -28,4 +28,18 @@ export const getImageSrc = async (path: string): Promise<string> => {
         toast.error("Error loading image preview");
         throw error;
     }
-};
\ No newline at end of file
+};
+
+// Custom protocols are exposed as http://<scheme>.localhost on Windows and Android
+const MOD_IMAGE_ORIGIN = /windows|android/i.test(navigator.userAgent)
+    ? "http://modimg.localhost"
+    : "modimg://localhost";
+
+export const getModImageUrl = (
+    modId: string,
+    kind: "thumbnail" | `gallery/${number}` = "thumbnail",
+    size?: number
+): string => {
+    const url = `${MOD_IMAGE_ORIGIN}/${encodeURIComponent(modId)}/${kind}`;
+    return size ? `${url}?size=${size}` : url;
+};
@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from "react-toastify";
+import { describeError } from "./error.service";
+
+export interface HistoryItem {
+  id: string;
+  label: string;
+  // Unix millis
+  createdAt: number;
+  changes: number;
+  undone: boolean;
+}
+
+export const getJournalHistory = async (path: string): Promise<HistoryItem[]> => {
+  try {
+    return await invoke<HistoryItem[]>("get_journal_history", { path });
+  } catch (error) {
+    console.error("Error loading history:", error);
+    toast.error("Error loading history");
+    throw error;
+  }
+};
+
+export const undoLast = async (path: string): Promise<string> => {
+  try {
+    const label = await invoke<string>("undo_last", { path });
+    toast.success(`Undid: ${label}`);
+    return label;
+  } catch (error) {
+    console.error("Error undoing:", error);
+    toast.error(describeError(error, "Error undoing"));
+    throw error;
+  }
+};
+
+export const redo = async (path: string): Promise<string> => {
+  try {
+    const label = await invoke<string>("redo", { path });
+    toast.success(`Redid: ${label}`);
+    return label;
+  } catch (error) {
+    console.error("Error redoing:", error);
+    toast.error(describeError(error, "Error redoing"));
+    throw error;
+  }
+};
@@ -1,13 +1,61 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { Mod } from "../interfaces/Mod.interface";
+import { HashConflict, Mod } from "../interfaces/Mod.interface";
 import { toast } from "react-toastify";
+import { AppError } from "../interfaces/AppError.interface";
+import { describeError } from "./error.service";
 
 interface DownloadProgress {
   downloaded: number;
   total: number;
 }
 
+interface ModInfoResult {
+  mod: Mod;
+  conflicts: HashConflict[];
+}
+
+export type BulkAction =
+  | { type: "enable" }
+  | { type: "disable" }
+  | { type: "setCategory"; category: string }
+  | { type: "addTag"; tag: string }
+  | { type: "removeTag"; tag: string }
+  | { type: "delete" };
+
+export interface BulkOperation {
+  modId: string;
+  action: BulkAction;
+}
+
+export interface BulkResult {
+  modId: string;
+  modName: string;
+  action: BulkAction;
+  status: "applied" | "unchanged" | "failed" | "rolledBack" | "skipped";
+  error: AppError | null;
+}
+
+export interface BulkReport {
+  results: BulkResult[];
+  rolledBack: boolean;
+  conflicts: HashConflict[];
+}
+
+export interface ModPage {
+  mods: Mod[];
+  total: number;
+  page: number;
+  pageSize: number;
+}
+
+export const warnConflicts = (conflicts: HashConflict[]) => {
+  conflicts.forEach((conflict) => {
+    const names = conflict.mods.map((mod) => mod.name).join(", ");
+    toast.warning(`Hash ${conflict.hash} is overridden by: ${names}`);
+  });
+};
+
 export const addMod = async (path: string, to: string): Promise<Mod> => {
   // Ensure the path and to is not an empty string
   if (!path || !to) {
@@ -21,16 +69,17 @@ export const addMod = async (path: string, to: string): Promise<Mod> => {
     return result;
   } catch (error) {
     console.error("Error adding mod:", error);
-    toast.error("Error adding mod");
+    toast.error(describeError(error, "Error adding mod"));
     throw error;
   }
 };
 
 export const setModInfo = async (mod: Mod): Promise<Mod> => {
   try {
-    const result: Mod = await invoke("set_mod_info", { modData: mod });
+    const result: ModInfoResult = await invoke("set_mod_info", { modData: mod });
     toast.success("Mod info updated successfully");
-    return result;
+    warnConflicts(result.conflicts);
+    return result.mod;
   } catch (error) {
     console.error("Error setting mod details:", error);
     toast.error("Error setting mod details");
@@ -62,6 +111,17 @@ export const setModThumbnail = async ({
   }
 };
 
+export const setModThumbnailFromClipboard = async (mod: Mod) => {
+  try {
+    await invoke("set_mod_thumbnail_from_clipboard", { path: mod.path });
+    toast.success("Mod thumbnail updated successfully");
+  } catch (error) {
+    console.error("Error setting mod thumbnail from clipboard:", error);
+    toast.error("Clipboard does not contain an image");
+    throw error;
+  }
+};
+
 export const downloadMod = async (url: string, to: string) => {
   const toastId = toast.loading("Preparing download...");
 
@@ -100,7 +160,7 @@ export const downloadMod = async (url: string, to: string) => {
 
     // Update toast on error
     toast.update(toastId, {
-      render: "Error downloading mod",
+      render: describeError(error, "Error downloading mod"),
       type: "error",
       isLoading: false,
       autoClose: 10000,
@@ -112,11 +172,70 @@ export const downloadMod = async (url: string, to: string) => {
 export const deleteMod = async (mod: Mod) => {
   try {
     const result = await invoke("delete_mod", { path: mod.path });
-    toast.success("Mod deleted successfully");
+    toast.success("Mod moved to the trash");
     return result;
   } catch (error) {
     console.error("Error deleting mod:", error);
-    toast.error("Error deleting mod");
+    toast.error(describeError(error, "Error deleting mod"));
+    throw error;
+  }
+};
+
+// e.g. `category:Jinhsi enabled:false tag:"nsfw free" sort:installed-desc`
+export const queryMods = async (
+  path: string,
+  query: string,
+  page = 0,
+  pageSize?: number
+): Promise<ModPage> => {
+  try {
+    return await invoke<ModPage>("query_mods", { path, query, page, pageSize });
+  } catch (error) {
+    console.error("Error searching mods:", error);
+    toast.error(`Invalid search: ${describeError(error, "unknown error")}`);
+    throw error;
+  }
+};
+
+// Full-text search over descriptions, readmes and ini comments, best match first
+export const searchMods = async (
+  path: string,
+  query: string,
+  limit?: number
+): Promise<string[]> => {
+  try {
+    return await invoke<string[]>("search_mods", { path, query, limit });
+  } catch (error) {
+    console.error("Error searching mods:", error);
+    toast.error("Error searching mods");
+    throw error;
+  }
+};
+
+export const bulkUpdateMods = async (
+  path: string,
+  operations: BulkOperation[],
+  rollbackOnError = true
+): Promise<BulkReport> => {
+  try {
+    const report = await invoke<BulkReport>("bulk_update_mods", {
+      path,
+      operations,
+      rollbackOnError,
+    });
+    const failed = report.results.filter((result) => result.status === "failed");
+    if (report.rolledBack) {
+      toast.error(`${failed[0]?.error?.message ?? "An operation failed"}, all changes were rolled back`);
+    } else if (failed.length > 0) {
+      toast.warning(`${failed.length} of ${report.results.length} operations failed`);
+    } else {
+      toast.success("Mods updated successfully");
+    }
+    warnConflicts(report.conflicts);
+    return report;
+  } catch (error) {
+    console.error("Error updating mods:", error);
+    toast.error("Error updating mods");
     throw error;
   }
 };
@@ -1,6 +1,8 @@
 import { toast } from "react-toastify";
 import { Preset } from "../interfaces/Preset.interface";
 import { invoke } from "@tauri-apps/api/core";
+import { HashConflict } from "../interfaces/Mod.interface";
+import { warnConflicts } from "./mod.service";
 
 interface BackendPreset {
   name: string;
@@ -61,11 +63,12 @@ export const deletePreset = async (modDirPath: string, presetId: string) => {
 
 export const applyPreset = async (modDirPath: string, presetId: string) => {
   try {
-    await invoke("apply_preset", {
+    const conflicts = await invoke<HashConflict[]>("apply_preset", {
       path: modDirPath,
       presetId,
     });
     toast.success("Preset applied successfully");
+    warnConflicts(conflicts);
   } catch (error) {
     console.error("Error applying preset:", error);
     toast.error("Error applying preset");
@@ -0,0 +1,48 @@
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from "react-toastify";
+import { describeError } from "./error.service";
+
+export interface TrashEntry {
+  id: string;
+  name: string;
+  originalPath: string;
+  // Unix millis
+  deletedAt: number;
+  // Bytes
+  size: number;
+}
+
+export const listTrash = async (path: string): Promise<TrashEntry[]> => {
+  try {
+    return await invoke<TrashEntry[]>("list_trash", { path });
+  } catch (error) {
+    console.error("Error loading trash:", error);
+    toast.error("Error loading trash");
+    throw error;
+  }
+};
+
+export const restoreFromTrash = async (path: string, entry: TrashEntry): Promise<string> => {
+  try {
+    const restoredPath = await invoke<string>("restore_from_trash", { path, id: entry.id });
+    toast.success(`${entry.name} restored`);
+    return restoredPath;
+  } catch (error) {
+    console.error("Error restoring mod:", error);
+    toast.error(describeError(error, "Error restoring mod"));
+    throw error;
+  }
+};
+
+// Empties the whole trash when no ids are given
+export const purgeTrash = async (path: string, ids?: string[]): Promise<number> => {
+  try {
+    const purged = await invoke<number>("purge_trash", { path, ids: ids ?? null });
+    toast.success(`Permanently deleted ${purged} mods`);
+    return purged;
+  } catch (error) {
+    console.error("Error purging trash:", error);
+    toast.error("Error purging trash");
+    throw error;
+  }
+};